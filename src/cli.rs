@@ -1,2 +1,4 @@
+pub mod args;
 pub mod game_updater;
-mod sudoku_printer;
+pub mod json;
+pub mod sudoku_printer;
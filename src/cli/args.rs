@@ -0,0 +1,390 @@
+use std::io::{IsTerminal, stdout};
+use std::iter::Peekable;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sudoku::{Algorithms, ClueTarget, Difficulty, Symmetry};
+
+pub const USAGE: &str = "\
+Usage:
+  sudoku [OPTIONS] [PATH]             solve the puzzle in PATH (default input.txt, - for stdin)
+  sudoku generate [OPTIONS]           generate a puzzle
+  sudoku rate [OPTIONS] PATH...       rate puzzles by the techniques they need
+  sudoku batch [OPTIONS] [PATH]       solve one puzzle per line (- or no PATH for stdin)
+
+Solve options:
+  -a, --algorithm <name>      bt, ce, dlx, cp, parallel or portfolio
+  -b, --box-size <RxC>        box shape, rows x columns
+      --throttle-ms <ms>      delay between redraws of the live board (default 100)
+      --no-animate            print the final board only
+      --pencil-marks          show the candidates of the empty cells
+      --count-solutions [n]   count solutions up to n (default 2) instead of solving
+      --explain               solve with human techniques, printing every step
+      --hint                  print the next logical step only
+      --timeout-ms <ms>       give up after ms milliseconds
+      --max-actions <n>       give up after n solver actions
+      --output <format>       text, json, or plain for the solved grid alone
+      --color <when>          auto, always or never (default auto)
+
+Generate options:
+      --seed <n>              seed for the random generator
+  -b, --box-size <RxC>        box shape, rows x columns (default 3x3)
+      --clues <n>             number of clues to aim for
+  -d, --difficulty <band>     easy, medium, hard or expert
+  -s, --symmetry <kind>       rotational, mirror or none
+  -o, --out-file <path>       write to a file instead of stdout
+
+Rate options:
+  -b, --box-size <RxC>        box shape, rows x columns
+
+Batch options:
+  -a, --algorithm <name>      as for solve
+      --output <format>       text, plain (same as text) or json per puzzle
+  -o, --out-file <path>       write the solutions to a file instead of stdout
+      --timeout-ms <ms>       give up on a puzzle after ms milliseconds
+      --max-actions <n>       give up on a puzzle after n solver actions
+
+  -h, --help                  print this help";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// The grid alone in the `?` format the parser reads.
+    Plain,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// `Auto` colors only a terminal, and only when `NO_COLOR` isn't set.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && stdout().is_terminal()
+            }
+        }
+    }
+}
+
+pub struct SolveArgs {
+    pub path: String,
+    pub output: OutputFormat,
+    pub color: ColorChoice,
+    pub throttle_ms: Option<u64>,
+    pub animate: bool,
+    pub pencil_marks: bool,
+    pub algorithm: Option<Algorithms>,
+    pub box_size: Option<(usize, usize)>,
+    pub count_solutions: Option<usize>,
+    pub explain: bool,
+    pub hint: bool,
+    pub timeout_ms: Option<u64>,
+    pub max_actions: Option<u64>,
+}
+
+pub struct GenerateArgs {
+    pub seed: u64,
+    pub box_size: (usize, usize),
+    pub target: ClueTarget,
+    pub symmetry: Symmetry,
+    pub out_file: Option<String>,
+}
+
+pub struct RateArgs {
+    pub paths: Vec<String>,
+    pub box_size: Option<(usize, usize)>,
+}
+
+pub struct BatchArgs {
+    pub path: Option<String>,
+    pub algorithm: Option<Algorithms>,
+    pub output: OutputFormat,
+    pub out_file: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub max_actions: Option<u64>,
+}
+
+pub enum Command {
+    Solve(SolveArgs),
+    Generate(GenerateArgs),
+    Rate(RateArgs),
+    Batch(BatchArgs),
+}
+
+/// Reads the command line, failing with a message meant to be shown above
+/// the usage on unknown options, missing values or values that don't parse.
+pub fn read_args() -> Result<Command, String> {
+    let mut args = std::env::args().skip(1).peekable();
+
+    if args.next_if(|arg| arg == "generate").is_some() {
+        read_generate_args(args).map(Command::Generate)
+    } else if args.next_if(|arg| arg == "rate").is_some() {
+        read_rate_args(args).map(Command::Rate)
+    } else if args.next_if(|arg| arg == "batch").is_some() {
+        read_batch_args(args).map(Command::Batch)
+    } else {
+        read_solve_args(args).map(Command::Solve)
+    }
+}
+
+/// `--help` is accepted anywhere a flag is, after a subcommand too.
+fn print_help() -> ! {
+    println!("{USAGE}");
+    std::process::exit(0);
+}
+
+fn flag_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{flag} needs a value"))
+}
+
+fn parse_flag_value<T>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Result<T, String> {
+    let val = flag_value(args, flag)?;
+    parse(&val).ok_or_else(|| format!("Invalid value for {flag}: {val}"))
+}
+
+fn number<T: FromStr>(val: &str) -> Option<T> {
+    val.parse().ok()
+}
+
+/// Anything that isn't a flag is a positional argument, `-` included.
+fn positional(arg: String) -> Result<String, String> {
+    if arg.starts_with('-') && arg != "-" {
+        Err(format!("Unknown option {arg}"))
+    } else {
+        Ok(arg)
+    }
+}
+
+fn parse_box_size(val: &str) -> Option<(usize, usize)> {
+    let val = val.to_lowercase();
+    let (height, width) = val.split_once('x')?;
+
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+fn parse_algorithm(val: &str) -> Option<Algorithms> {
+    match val.to_lowercase().as_str() {
+        "backtracking" | "bt" => Some(Algorithms::Backtracking),
+        "candidate" | "candidateelection" | "ce" => Some(Algorithms::CandidateElection),
+        "dancinglinks" | "dancing-links" | "dlx" => Some(Algorithms::DancingLinks),
+        "propagation" | "constraintpropagation" | "cp" => Some(Algorithms::ConstraintPropagation),
+        "parallel" | "par" => Some(Algorithms::Parallel),
+        "portfolio" | "all" => Some(Algorithms::Portfolio),
+        _ => None,
+    }
+}
+
+fn parse_output_format(val: &str) -> Option<OutputFormat> {
+    match val.to_lowercase().as_str() {
+        "text" => Some(OutputFormat::Text),
+        "json" => Some(OutputFormat::Json),
+        "plain" => Some(OutputFormat::Plain),
+        _ => None,
+    }
+}
+
+fn parse_color(val: &str) -> Option<ColorChoice> {
+    match val.to_lowercase().as_str() {
+        "auto" => Some(ColorChoice::Auto),
+        "always" => Some(ColorChoice::Always),
+        "never" => Some(ColorChoice::Never),
+        _ => None,
+    }
+}
+
+fn parse_difficulty(val: &str) -> Option<Difficulty> {
+    match val.to_lowercase().as_str() {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        "expert" => Some(Difficulty::Expert),
+        _ => None,
+    }
+}
+
+fn parse_symmetry(val: &str) -> Option<Symmetry> {
+    match val.to_lowercase().as_str() {
+        "none" => Some(Symmetry::None),
+        "rotational" => Some(Symmetry::Rotational),
+        "mirror" => Some(Symmetry::Mirror),
+        _ => None,
+    }
+}
+
+fn read_solve_args(mut args: Peekable<impl Iterator<Item = String>>) -> Result<SolveArgs, String> {
+    let mut path: Option<String> = None;
+    let mut output = OutputFormat::Text;
+    let mut color = ColorChoice::Auto;
+    let mut throttle_ms: Option<u64> = None;
+    let mut animate = true;
+    let mut pencil_marks = false;
+    let mut algorithm: Option<Algorithms> = None;
+    let mut box_size: Option<(usize, usize)> = None;
+    let mut count_solutions: Option<usize> = None;
+    let mut explain = false;
+    let mut hint = false;
+    let mut timeout_ms: Option<u64> = None;
+    let mut max_actions: Option<u64> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => print_help(),
+            "--throttle-ms" => throttle_ms = Some(parse_flag_value(&mut args, &arg, number)?),
+            "--no-animate" => animate = false,
+            "--pencil-marks" => pencil_marks = true,
+            "--algorithm" | "-a" => {
+                algorithm = Some(parse_flag_value(&mut args, &arg, parse_algorithm)?)
+            }
+            "--box-size" | "-b" => {
+                box_size = Some(parse_flag_value(&mut args, &arg, parse_box_size)?)
+            }
+            "--count-solutions" => {
+                let limit = args.next_if(|val| val.parse::<usize>().is_ok());
+                count_solutions = Some(limit.map_or(2, |val| val.parse().unwrap()));
+            }
+            "--explain" => explain = true,
+            "--hint" => hint = true,
+            "--timeout-ms" => timeout_ms = Some(parse_flag_value(&mut args, &arg, number)?),
+            "--max-actions" => max_actions = Some(parse_flag_value(&mut args, &arg, number)?),
+            "--output" => output = parse_flag_value(&mut args, &arg, parse_output_format)?,
+            "--color" => color = parse_flag_value(&mut args, &arg, parse_color)?,
+            _ => {
+                if let Some(path) = &path {
+                    return Err(format!(
+                        "Unexpected argument {arg}, the path is already {path}"
+                    ));
+                }
+
+                path = Some(positional(arg)?);
+            }
+        }
+    }
+
+    Ok(SolveArgs {
+        path: path.unwrap_or_else(|| "input.txt".to_string()),
+        output,
+        color,
+        throttle_ms,
+        animate,
+        pencil_marks,
+        algorithm,
+        box_size,
+        count_solutions,
+        explain,
+        hint,
+        timeout_ms,
+        max_actions,
+    })
+}
+
+fn read_generate_args(
+    mut args: Peekable<impl Iterator<Item = String>>,
+) -> Result<GenerateArgs, String> {
+    let mut seed: Option<u64> = None;
+    let mut box_size = (3, 3);
+    let mut target = ClueTarget::Difficulty(Difficulty::Medium);
+    let mut symmetry = Symmetry::Rotational;
+    let mut out_file: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => print_help(),
+            "--seed" => seed = Some(parse_flag_value(&mut args, &arg, number)?),
+            "--box-size" | "-b" => box_size = parse_flag_value(&mut args, &arg, parse_box_size)?,
+            "--clues" => target = ClueTarget::Clues(parse_flag_value(&mut args, &arg, number)?),
+            "--difficulty" | "-d" => {
+                target =
+                    ClueTarget::Difficulty(parse_flag_value(&mut args, &arg, parse_difficulty)?)
+            }
+            "--symmetry" | "-s" => symmetry = parse_flag_value(&mut args, &arg, parse_symmetry)?,
+            "--out-file" | "-o" => out_file = Some(flag_value(&mut args, &arg)?),
+            _ => return Err(format!("Unexpected argument {arg}")),
+        }
+    }
+
+    Ok(GenerateArgs {
+        seed: seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+        }),
+        box_size,
+        target,
+        symmetry,
+        out_file,
+    })
+}
+
+fn read_rate_args(mut args: Peekable<impl Iterator<Item = String>>) -> Result<RateArgs, String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut box_size: Option<(usize, usize)> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => print_help(),
+            "--box-size" | "-b" => {
+                box_size = Some(parse_flag_value(&mut args, &arg, parse_box_size)?)
+            }
+            _ => paths.push(positional(arg)?),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err("rate needs at least one path".to_string());
+    }
+
+    Ok(RateArgs { paths, box_size })
+}
+
+fn read_batch_args(mut args: Peekable<impl Iterator<Item = String>>) -> Result<BatchArgs, String> {
+    let mut path: Option<String> = None;
+    let mut algorithm: Option<Algorithms> = None;
+    let mut output = OutputFormat::Text;
+    let mut out_file: Option<String> = None;
+    let mut timeout_ms: Option<u64> = None;
+    let mut max_actions: Option<u64> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => print_help(),
+            "--algorithm" | "-a" => {
+                algorithm = Some(parse_flag_value(&mut args, &arg, parse_algorithm)?)
+            }
+            "--output" => output = parse_flag_value(&mut args, &arg, parse_output_format)?,
+            "--out-file" | "-o" => out_file = Some(flag_value(&mut args, &arg)?),
+            "--timeout-ms" => timeout_ms = Some(parse_flag_value(&mut args, &arg, number)?),
+            "--max-actions" => max_actions = Some(parse_flag_value(&mut args, &arg, number)?),
+            _ => {
+                if let Some(path) = &path {
+                    return Err(format!(
+                        "Unexpected argument {arg}, the path is already {path}"
+                    ));
+                }
+
+                path = Some(positional(arg)?);
+            }
+        }
+    }
+
+    Ok(BatchArgs {
+        path,
+        algorithm,
+        output,
+        out_file,
+        timeout_ms,
+        max_actions,
+    })
+}
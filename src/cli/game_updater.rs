@@ -1,74 +1,112 @@
+use std::io::{Write, stdout};
 use std::sync::mpsc::Receiver;
 use std::time::{Duration, Instant};
 
-use crate::cli::sudoku_printer::SudokuPrinter;
-use crate::sudoku::board::SudokuCell;
+use sudoku::{BoardEvent, SudokuCell};
+
+use crate::cli::sudoku_printer::{Focus, SudokuPrinter};
 
 pub struct GameUpdater {
-    board_rx: Receiver<CliChannelEvent>,
+    board_rx: Receiver<BoardEvent>,
     throttle_ms: u64,
     sudoku: SudokuPrinter,
-}
-
-pub enum CliChannelEvent {
-    Update(SudokuCell),
-    ForceLastPrint,
+    /// Lines of the last frame, the cursor goes back up over them to redraw.
+    drawn_lines: usize,
 }
 
 impl GameUpdater {
-    pub fn new(board_rx: Receiver<CliChannelEvent>, throttle_ms: Option<u64>) -> Self {
+    pub fn new(
+        board_rx: Receiver<BoardEvent>,
+        throttle_ms: Option<u64>,
+        pencil_marks: bool,
+        box_width: usize,
+        box_height: usize,
+    ) -> Self {
         GameUpdater {
             board_rx,
-            throttle_ms: throttle_ms.or(Some(100)).unwrap(),
-            sudoku: SudokuPrinter::new(),
+            throttle_ms: throttle_ms.unwrap_or(100),
+            sudoku: SudokuPrinter::new(box_width, box_height, pencil_marks),
+            drawn_lines: 0,
         }
     }
 
-    fn print(&self, message: String) -> String {
-        print!("{}[2J", 27 as char);
-        println!("{message}");
+    /// Draws the board over the previous frame rather than clearing the
+    /// screen, which flickers.
+    fn print(&mut self) {
+        let frame = self.sudoku.to_string();
+        let mut out = stdout().lock();
+
+        if self.drawn_lines > 0 {
+            let _ = write!(out, "{}[{}F", 27 as char, self.drawn_lines);
+        }
 
-        message
+        let _ = write!(out, "{frame}");
+        let _ = out.flush();
+        self.drawn_lines = frame.lines().count();
     }
 
-    fn format(&mut self, sudoku_message: SudokuCell) -> String {
+    fn update(&mut self, sudoku_message: SudokuCell) {
         let cell = &mut self.sudoku.board[sudoku_message.x][sudoku_message.y];
         cell.value = sudoku_message.value;
         cell.editable = sudoku_message.editable;
-
-        format!("{}", self.sudoku)
     }
 
+    /// Redraws at most once per throttle interval while the solver runs, and
+    /// once more with the final board. Only used with escape codes, frames
+    /// can't be drawn over each other without them.
     pub fn listen(&mut self) -> Result<(), String> {
-        let mut last_print = Instant::now() - Duration::from_millis(self.throttle_ms);
         let interval = Duration::from_millis(self.throttle_ms);
-        let mut last_message: Option<String> = None;
-
-        loop {
-            match self.board_rx.recv() {
-                Ok(sudoku_message) => {
-                    let now = Instant::now();
-                    match sudoku_message {
-                        CliChannelEvent::Update(sudoku_cell) => {
-                            let out = self.format(sudoku_cell);
-
-                            if now.duration_since(last_print) >= interval {
-                                last_print = now;
-                            }
+        let mut last_print = Instant::now();
 
-                            last_message = Some(out);
-                        }
-                        CliChannelEvent::ForceLastPrint => break,
-                    }
+        while let Ok(sudoku_message) = self.board_rx.recv() {
+            match sudoku_message {
+                BoardEvent::Update(sudoku_cell) => self.update(sudoku_cell),
+                BoardEvent::Try { x, y, value } => {
+                    self.sudoku.focus = Some(Focus::Trying { x, y, value })
                 }
-                Err(_) => break,
+                BoardEvent::Accept(sudoku_cell) => {
+                    self.update(sudoku_cell);
+                    self.sudoku.focus = Some(Focus::Accepted {
+                        x: sudoku_cell.x,
+                        y: sudoku_cell.y,
+                    })
+                }
+                BoardEvent::Reject {
+                    x,
+                    y,
+                    value,
+                    conflict_with,
+                } => {
+                    self.sudoku.focus = Some(Focus::Rejected {
+                        x,
+                        y,
+                        value,
+                        conflict_with,
+                    })
+                }
+                BoardEvent::Backtrack(sudoku_cell) => {
+                    self.update(sudoku_cell);
+                    self.sudoku.focus = Some(Focus::Backtracked {
+                        x: sudoku_cell.x,
+                        y: sudoku_cell.y,
+                    })
+                }
+                BoardEvent::Candidates { x, y, candidates } => {
+                    self.sudoku.candidates[x][y] = candidates
+                }
+                BoardEvent::Finished => break,
             }
-        }
 
-        if let Some(message) = last_message {
-            self.print(message);
+            let now = Instant::now();
+            if now.duration_since(last_print) >= interval {
+                last_print = now;
+                self.print();
+            }
         }
 
+        self.sudoku.focus = None;
+        self.print();
+
         Ok(())
     }
 }
@@ -0,0 +1,105 @@
+use serde_json::{Value, json};
+use sudoku::{Algorithms, Grid, SolveOutcome, SolveStatus, SudokuError};
+
+/// Rows of numbers, `null` for the empty cells.
+fn grid_value(grid: &Grid) -> Value {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&value| match value {
+                    0 => Value::Null,
+                    value => json!(value),
+                })
+                .collect::<Value>()
+        })
+        .collect()
+}
+
+/// One solve as a JSON document. `solution` is `null` unless the puzzle
+/// was solved.
+pub fn solve_document(algorithm: Algorithms, input: &Grid, outcome: &SolveOutcome) -> Value {
+    let solution = match outcome.status {
+        SolveStatus::Solved => grid_value(&outcome.grid),
+        SolveStatus::Unsolvable | SolveStatus::Aborted => Value::Null,
+    };
+
+    json!({
+        "algorithm": algorithm.name(),
+        "status": outcome.status.name(),
+        "input": grid_value(input),
+        "solution": solution,
+        "actions": outcome.stats.actions,
+        "branches": outcome.stats.branches,
+        "elapsed": outcome.stats.elapsed.as_secs_f64(),
+    })
+}
+
+/// A batch line that couldn't be read as a puzzle.
+pub fn invalid_document(error: &SudokuError) -> Value {
+    json!({
+        "status": "invalid",
+        "error": error.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sudoku::SolveStats;
+
+    use super::*;
+
+    fn outcome(status: SolveStatus) -> SolveOutcome {
+        SolveOutcome {
+            status,
+            grid: vec![vec![1, 2], vec![2, 1]],
+            stats: SolveStats {
+                actions: 12,
+                branches: 3,
+                elapsed: Duration::from_millis(250),
+            },
+        }
+    }
+
+    #[test]
+    fn documents_survive_a_round_trip() {
+        let input = vec![vec![1, 0], vec![0, 0]];
+        let document = solve_document(
+            Algorithms::DancingLinks,
+            &input,
+            &outcome(SolveStatus::Solved),
+        );
+        let parsed: Value = serde_json::from_str(&document.to_string()).unwrap();
+
+        assert_eq!(parsed, document);
+        assert_eq!(
+            parsed,
+            json!({
+                "algorithm": "dancing links",
+                "status": "solved",
+                "input": [[1, null], [null, null]],
+                "solution": [[1, 2], [2, 1]],
+                "actions": 12,
+                "branches": 3,
+                "elapsed": 0.25,
+            })
+        );
+    }
+
+    #[test]
+    fn only_solved_puzzles_have_a_solution() {
+        for status in [SolveStatus::Unsolvable, SolveStatus::Aborted] {
+            let document = solve_document(Algorithms::Backtracking, &Vec::new(), &outcome(status));
+
+            assert_eq!(document["solution"], Value::Null);
+            assert_eq!(document["status"], status.name());
+        }
+
+        let invalid = invalid_document(&SudokuError::NoSolution);
+        assert_eq!(
+            invalid,
+            json!({"status": "invalid", "error": "The puzzle has no solution"})
+        );
+    }
+}
@@ -1,55 +1,231 @@
-use crate::sudoku::board::{SudokuBoard, SudokuCell};
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use std::fmt;
+use sudoku::{CandidateMask, CellType, SudokuBoard, SudokuCell};
 
-const BOARD_DIVIDER: &str = " |";
-const BOARD_MAX_NUMBER: usize = SudokuBoard::BOARD_MAX_NUMBER;
-const BOARD_N: usize = SudokuBoard::BOARD_N;
-const BOARD_LENGTH: usize = (BOARD_MAX_NUMBER * 2 - 1) + BOARD_DIVIDER.len() * 2;
+const BOARD_DIVIDER: &str = "|";
+
+/// What the solver last did, highlighted on the board.
+#[derive(Clone, Copy)]
+pub enum Focus {
+    Trying {
+        x: usize,
+        y: usize,
+        value: CellType,
+    },
+    Accepted {
+        x: usize,
+        y: usize,
+    },
+    /// Both the rejected value and the cell it clashes with are flashed.
+    Rejected {
+        x: usize,
+        y: usize,
+        value: CellType,
+        conflict_with: (usize, usize),
+    },
+    Backtracked {
+        x: usize,
+        y: usize,
+    },
+}
 
 pub struct SudokuPrinter {
-    pub board: [[SudokuCell; BOARD_MAX_NUMBER]; BOARD_MAX_NUMBER],
+    pub board: Vec<Vec<SudokuCell>>,
+    pub focus: Option<Focus>,
+    pub candidates: Vec<Vec<CandidateMask>>,
+    /// Draws each empty cell as a box-shaped mini-grid of its candidates,
+    /// like pencil marks on paper.
+    pub pencil_marks: bool,
+    box_width: usize,
+    box_height: usize,
 }
 
 impl SudokuPrinter {
-    pub fn new() -> Self {
+    pub fn new(box_width: usize, box_height: usize, pencil_marks: bool) -> Self {
+        let board_max_number = box_width * box_height;
+
         SudokuPrinter {
-            board: [[SudokuCell {
-                editable: false,
-                value: None,
-                x: 0,
-                y: 0,
-            }; 9]; 9],
+            board: vec![
+                vec![
+                    SudokuCell {
+                        editable: false,
+                        value: None,
+                        x: 0,
+                        y: 0,
+                    };
+                    board_max_number
+                ];
+                board_max_number
+            ],
+            focus: None,
+            candidates: vec![vec![0; board_max_number]; board_max_number],
+            pencil_marks,
+            box_width,
+            box_height,
+        }
+    }
+
+    /// The board as the solver left it, for printing once when there is no
+    /// live board.
+    pub fn from_board(board: &SudokuBoard, pencil_marks: bool) -> Self {
+        let mut printer = SudokuPrinter::new(board.box_width(), board.box_height(), pencil_marks);
+
+        for x in 0..board.board_max_number() {
+            for y in 0..board.board_max_number() {
+                if let Ok(&cell) = board.find_cell_from_coordinates(x, y) {
+                    printer.board[x][y] = cell;
+                    if cell.value.is_none() {
+                        printer.candidates[x][y] = board.candidates(x, y);
+                    }
+                }
+            }
+        }
+
+        printer
+    }
+
+    fn board_length(&self, cell_width: usize) -> usize {
+        let boxes_per_line = self.board.len() / self.box_width;
+
+        boxes_per_line * (BOARD_DIVIDER.len() + self.box_width * (cell_width + 1))
+    }
+
+    fn is_focused(&self, x: usize, y: usize) -> bool {
+        match self.focus {
+            Some(
+                Focus::Trying {
+                    x: focus_x,
+                    y: focus_y,
+                    ..
+                }
+                | Focus::Accepted {
+                    x: focus_x,
+                    y: focus_y,
+                }
+                | Focus::Backtracked {
+                    x: focus_x,
+                    y: focus_y,
+                },
+            ) => (focus_x, focus_y) == (x, y),
+            Some(Focus::Rejected {
+                x: focus_x,
+                y: focus_y,
+                conflict_with,
+                ..
+            }) => (focus_x, focus_y) == (x, y) || conflict_with == (x, y),
+            None => false,
+        }
+    }
+
+    /// One character of the mini-grid drawn for cell `(x, y)`. Filled and
+    /// highlighted cells show their value in the middle.
+    fn format_pencil_mark(&self, x: usize, y: usize, mark_x: usize, mark_y: usize) -> String {
+        if self.board[x][y].value.is_some() || self.is_focused(x, y) {
+            return if (mark_x, mark_y) == (self.box_height / 2, self.box_width / 2) {
+                self.format_cell(x, y).to_string()
+            } else {
+                " ".to_string()
+            };
+        }
+
+        let value = (mark_x * self.box_width + mark_y + 1) as CellType;
+
+        if self.candidates[x][y] & SudokuBoard::value_mask(value) != 0 {
+            SudokuBoard::value_to_char(value)
+                .to_string()
+                .dimmed()
+                .to_string()
+        } else {
+            " ".to_string()
+        }
+    }
+
+    fn format_pencil_marks(&self) -> String {
+        let mut output = String::new();
+        let board_max_number = self.board.len();
+
+        for x in 0..board_max_number {
+            if x % self.box_height == 0 {
+                output.push_str(&"-".repeat(self.board_length(self.box_width)).on_white());
+                output.push('\n');
+            }
+
+            for mark_x in 0..self.box_height {
+                for y in 0..board_max_number {
+                    if y % self.box_width == 0 {
+                        output.push_str(&BOARD_DIVIDER.white());
+                    }
+
+                    for mark_y in 0..self.box_width {
+                        output.push_str(&self.format_pencil_mark(x, y, mark_x, mark_y));
+                    }
+                    output.push(' ');
+                }
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    fn format_cell(&self, x: usize, y: usize) -> ColoredString {
+        let cell = self.board[x][y];
+        let text =
+            |value: Option<CellType>| value.map_or('?', SudokuBoard::value_to_char).to_string();
+
+        match self.focus {
+            Some(Focus::Trying {
+                x: focus_x,
+                y: focus_y,
+                value,
+            }) if (focus_x, focus_y) == (x, y) => text(Some(value)).black().on_cyan(),
+            Some(Focus::Accepted {
+                x: focus_x,
+                y: focus_y,
+            }) if (focus_x, focus_y) == (x, y) => text(cell.value).black().on_green(),
+            Some(Focus::Rejected {
+                x: focus_x,
+                y: focus_y,
+                value,
+                ..
+            }) if (focus_x, focus_y) == (x, y) => text(Some(value)).white().on_red(),
+            Some(Focus::Rejected { conflict_with, .. }) if conflict_with == (x, y) => {
+                text(cell.value).white().on_red()
+            }
+            Some(Focus::Backtracked {
+                x: focus_x,
+                y: focus_y,
+            }) if (focus_x, focus_y) == (x, y) => text(cell.value).black().on_magenta(),
+            _ => match cell.value {
+                Some(_) if cell.editable => text(cell.value).yellow(),
+                Some(_) => text(cell.value).blue(),
+                None => text(None).red(),
+            },
         }
     }
 }
 
 impl fmt::Display for SudokuPrinter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.pencil_marks {
+            return write!(f, "{}", self.format_pencil_marks());
+        }
+
         let mut output = String::new();
         let mut previous_board_row_index: Option<usize> = None;
+        let board_max_number = self.board.len();
 
-        for x in 0..BOARD_MAX_NUMBER {
-            let board_row_index = x / BOARD_N;
+        for x in 0..board_max_number {
+            let board_row_index = x / self.box_height;
             let mut line_str = String::new();
             let mut box_line_str = String::new();
 
-            for y in 0..BOARD_MAX_NUMBER {
-                let cell = self.board[x][y];
-                let value = match cell.value {
-                    Some(match_value) => {
-                        if cell.editable {
-                            match_value.to_string().yellow()
-                        } else {
-                            match_value.to_string().blue()
-                        }
-                    }
-                    None => "?".to_string().red(),
-                };
+            for y in 0..board_max_number {
+                let value = self.format_cell(x, y);
                 box_line_str.push_str(&format!("{value} "));
 
-                if (y + 1) % 3 == 0 {
-                    line_str.push_str(&"|".white());
+                if (y + 1) % self.box_width == 0 {
+                    line_str.push_str(&BOARD_DIVIDER.white());
                     line_str.push_str(&box_line_str);
                     box_line_str.clear();
                 }
@@ -57,12 +233,12 @@ impl fmt::Display for SudokuPrinter {
 
             if previous_board_row_index != Some(board_row_index) {
                 previous_board_row_index = Some(board_row_index);
-                output.push_str(&"-".repeat(BOARD_LENGTH.into()).on_white());
-                output.push_str("\n");
+                output.push_str(&"-".repeat(self.board_length(1)).on_white());
+                output.push('\n');
             }
 
             output.push_str(&line_str);
-            output.push_str("\n");
+            output.push('\n');
         }
 
         write!(f, "{}", output)
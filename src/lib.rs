@@ -0,0 +1,27 @@
+//! Sudoku boards of any box shape up to 64x64, several solvers, a puzzle
+//! generator and a human-style technique solver. The `sudoku` binary is a
+//! command line front end for this library.
+
+mod sudoku;
+
+pub use sudoku::{
+    algorithms::{
+        Algorithms,
+        limits::{CancellationToken, SolveLimits},
+        outcome::{SolveOutcome, SolveStats, SolveStatus},
+        portfolio::{PortfolioOutcome, solve_portfolio},
+        solutions::{SolutionCount, count_solutions, count_solutions_parallel},
+        solve, solve_with_limits,
+    },
+    batch::BatchStats,
+    board::{BoardEvent, CandidateMask, CellType, Grid, SudokuBoard, SudokuCell},
+    error::{Conflict, SudokuError},
+    generator::{ClueTarget, GeneratedPuzzle, Generator, GeneratorOptions, Symmetry},
+    hint::{Hint, hint},
+    parser::{
+        InputFormat, Puzzle, parse, parse_grid, parse_line, parse_puzzles, parse_sdk, parse_sdm,
+        read_file, read_puzzles,
+    },
+    rating::{Difficulty, Rating, rate},
+    techniques::{Elimination, LogicalSolver, Placement, SolveStep, Technique},
+};
@@ -1,116 +1,452 @@
 use std::{
-    fs::read_to_string,
+    fs::{File, write},
+    io::{self, BufRead, BufReader, BufWriter, Write},
     sync::mpsc,
-    thread::{self},
+    thread,
+    time::Duration,
 };
 
-use crate::{
-    cli::game_updater::{CliChannelEvent, GameUpdater},
-    sudoku::{
-        algorithms::{
-            backtracking::Backtracking, base_algorithms::BaseAlgorithms,
-            candidate_election::CandidateElection,
-        },
-        board::CellType,
-    },
+use sudoku::{
+    Algorithms, BatchStats, BoardEvent, Generator, GeneratorOptions, Grid, InputFormat,
+    LogicalSolver, PortfolioOutcome, Puzzle, Rating, SolutionCount, SolveLimits, SolveStatus,
+    SudokuBoard, SudokuError, count_solutions, count_solutions_parallel, hint, parse_line,
+    parse_puzzles, rate, read_puzzles, solve_portfolio, solve_with_limits,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Algorithms {
-    Backtracking,
-    CandidateElection,
-}
+use crate::cli::{
+    args::{BatchArgs, Command, GenerateArgs, OutputFormat, RateArgs, SolveArgs, USAGE, read_args},
+    game_updater::GameUpdater,
+    json,
+    sudoku_printer::SudokuPrinter,
+};
 
 mod cli;
-mod sudoku;
 
 fn main() {
-    let (board_tx, board_rx) = mpsc::channel::<CliChannelEvent>();
-    let (throttle_ms, algorithm) = read_args();
-    let board_file_result = read_file("input.txt".to_owned());
-    let board_file = match board_file_result {
-        Ok(board_file) => board_file,
-        Err(msg) => panic!("{}", msg),
-    };
-    let board = sudoku::board::SudokuBoard::new(board_file, board_tx.clone());
-    let mut game_updater = GameUpdater::new(board_rx, throttle_ms);
-    let game_updater_thread = thread::spawn(move || {
-        let _ = game_updater.listen();
+    match read_args() {
+        Ok(Command::Solve(args)) => solve(args),
+        Ok(Command::Generate(args)) => generate(args),
+        Ok(Command::Rate(args)) => rate_files(args),
+        Ok(Command::Batch(args)) => batch(args),
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Reads the puzzles in a file, or in stdin for `-`.
+fn read_input(path: &str) -> Result<Vec<Puzzle>, SudokuError> {
+    if path != "-" {
+        return read_puzzles(path);
+    }
+
+    match io::read_to_string(io::stdin()) {
+        Ok(text) => parse_puzzles(&text, InputFormat::detect(&text)),
+        Err(source) => Err(SudokuError::Io {
+            path: "stdin".to_string(),
+            source,
+        }),
+    }
+}
+
+fn generate(args: GenerateArgs) {
+    let (box_width, box_height) = args.box_size;
+    let mut generator = Generator::new(GeneratorOptions {
+        seed: args.seed,
+        box_width,
+        box_height,
+        target: args.target,
+        symmetry: args.symmetry,
     });
+    let generated = match generator.generate() {
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
 
-    match board {
-        Ok(mut board) => {
-            let alg = algorithm.unwrap_or(Algorithms::CandidateElection);
+    eprintln!(
+        "Generated: seed={} clues={} difficulty={}",
+        args.seed, generated.clues, generated.difficulty
+    );
 
-            let _ = thread::spawn(move || match alg {
-                Algorithms::Backtracking => {
-                    let backtracking = Backtracking::new(&mut board);
-                    backtracking.resolve();
-                }
-                Algorithms::CandidateElection => {
-                    let candidate = CandidateElection::new(&mut board);
-                    candidate.resolve();
+    match args.out_file {
+        Some(path) => {
+            if let Err(error) = write(&path, generated.to_text()) {
+                eprintln!("Couldn't write {path}: {error}");
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", generated.to_text()),
+    }
+}
+
+fn rate_files(args: RateArgs) {
+    let mut ratings: Vec<(String, Rating)> = Vec::new();
+
+    for path in args.paths {
+        let puzzles = match read_input(&path) {
+            Ok(puzzles) => puzzles,
+            Err(message) => {
+                eprintln!("{path}: {message}");
+                continue;
+            }
+        };
+        let several = puzzles.len() > 1;
+
+        for (index, board_file) in puzzles.into_iter().enumerate() {
+            // Puzzles of an .sdm are told apart by their line.
+            let name = if several {
+                format!("{path}:{}", index + 1)
+            } else {
+                path.clone()
+            };
+            let board = match args.box_size {
+                Some((box_width, box_height)) => {
+                    SudokuBoard::with_box_size(board_file, box_width, box_height)
                 }
-            })
-            .join();
+                None => SudokuBoard::new(board_file),
+            };
+
+            match board.and_then(|board| rate(&board)) {
+                Ok(rating) => ratings.push((name, rating)),
+                Err(message) => eprintln!("{name}: {message}"),
+            }
         }
-        Err(message) => panic!("{message}"),
     }
 
-    let _ = board_tx.send(CliChannelEvent::ForceLastPrint);
-    let _ = game_updater_thread.join();
+    ratings.sort_by(|(_, first), (_, second)| first.score.total_cmp(&second.score));
+
+    for (path, rating) in ratings {
+        let hardest = match (rating.solved_logically, rating.hardest) {
+            (false, _) => "trial and error".to_string(),
+            (true, Some(technique)) => technique.to_string(),
+            (true, None) => "already solved".to_string(),
+        };
+
+        println!(
+            "{path}: {:.1} {} ({hardest}) steps={} branches={}",
+            rating.score, rating.difficulty, rating.steps, rating.branches
+        );
+    }
 }
 
-fn read_args() -> (Option<u64>, Option<Algorithms>) {
-    let mut throttle_ms: Option<u64> = None;
-    let mut algorithm: Option<Algorithms> = None;
-    let mut args = std::env::args().skip(1);
-
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--throttle-ms" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<u64>() {
-                        throttle_ms = Some(v);
+fn batch(args: BatchArgs) {
+    let input: Box<dyn BufRead> = match args.path.as_deref() {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(error) => {
+                eprintln!("Couldn't read {path}: {error}");
+                std::process::exit(1);
+            }
+        },
+    };
+    let mut output: BufWriter<Box<dyn Write>> = BufWriter::new(match &args.out_file {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(file),
+            Err(error) => {
+                eprintln!("Couldn't write {path}: {error}");
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(io::stdout().lock()),
+    });
+    let algorithm = args.algorithm.unwrap_or(Algorithms::CandidateElection);
+    let limits = SolveLimits {
+        cancel: None,
+        time_limit: args.timeout_ms.map(Duration::from_millis),
+        max_actions: args.max_actions,
+    };
+    let mut stats = BatchStats::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("Line {}: {error}", line_index + 1);
+                break;
+            }
+        };
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let result = match parse_line(line).and_then(SudokuBoard::new) {
+            Ok(mut board) => {
+                let input = board.grid();
+                let outcome = solve_with_limits(&mut board, algorithm, limits.clone());
+                stats.record(&outcome);
+
+                match (args.output, outcome.status) {
+                    (OutputFormat::Json, _) => {
+                        json::solve_document(algorithm, &input, &outcome).to_string()
                     }
+                    (OutputFormat::Text | OutputFormat::Plain, SolveStatus::Solved) => outcome
+                        .grid
+                        .iter()
+                        .flatten()
+                        .map(|&value| SudokuBoard::value_to_char(value))
+                        .collect(),
+                    (OutputFormat::Text | OutputFormat::Plain, status) => status.to_string(),
                 }
             }
-            "--algorithm" | "-a" => {
-                if let Some(val) = args.next() {
-                    match val.to_lowercase().as_str() {
-                        "backtracking" | "bt" => algorithm = Some(Algorithms::Backtracking),
-                        "candidate" | "candidateelection" | "ce" => {
-                            algorithm = Some(Algorithms::CandidateElection)
-                        }
-                        _ => {}
-                    }
+            Err(error) => {
+                eprintln!("Line {}: {error}", line_index + 1);
+                stats.record_invalid();
+
+                match args.output {
+                    OutputFormat::Json => json::invalid_document(&error).to_string(),
+                    OutputFormat::Text | OutputFormat::Plain => "invalid".to_string(),
                 }
             }
-            _ => {}
+        };
+
+        if let Err(error) = writeln!(output, "{result}") {
+            eprintln!("Couldn't write the solutions: {error}");
+            std::process::exit(1);
         }
     }
 
-    (throttle_ms, algorithm)
+    let _ = output.flush();
+    eprintln!("{stats}");
 }
 
-fn read_file(file_path: String) -> Result<Vec<Vec<Option<CellType>>>, String> {
-    let mut board_file: Vec<Vec<Option<CellType>>> = Vec::new();
-    for line in match read_to_string(file_path) {
-        Ok(file) => file,
-        Err(_) => return Err("Couldn't read the file".to_string()),
-    }
-    .lines()
-    {
-        let list = line.chars().fold(
-            Vec::<Option<CellType>>::new(),
-            |mut acc: Vec<Option<u16>>, value| {
-                acc.push(value.to_digit(10).map(|digit| digit as CellType));
-                acc
-            },
+fn solve(args: SolveArgs) {
+    let (board_tx, board_rx) = mpsc::channel::<BoardEvent>();
+    let board_file = match read_input(&args.path) {
+        Ok(puzzles) => {
+            if puzzles.len() > 1 {
+                eprintln!(
+                    "{} holds {} puzzles, solving the first one",
+                    args.path,
+                    puzzles.len()
+                );
+            }
+            puzzles.into_iter().next().unwrap_or_default()
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+    let board_result = match args.box_size {
+        Some((box_width, box_height)) => {
+            SudokuBoard::with_box_size(board_file, box_width, box_height)
+        }
+        None => SudokuBoard::new(board_file),
+    };
+    // Sending events slows the solvers down, so the board only sends them
+    // when they are drawn live.
+    let ansi = args.color.enabled();
+    let live = args.output == OutputFormat::Text && args.animate && ansi;
+    let mut board = match board_result {
+        Ok(board) if !live => board,
+        Ok(board) if args.pencil_marks => {
+            board.with_candidate_events().with_events(board_tx.clone())
+        }
+        Ok(board) => board.with_events(board_tx.clone()),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(limit) = args.count_solutions {
+        let solution_count = match args.algorithm {
+            Some(Algorithms::Parallel) => count_solutions_parallel(&board, limit),
+            _ => count_solutions(&board, limit),
+        };
+        report_solution_count(solution_count, limit);
+    }
+
+    if args.hint {
+        show_hint(&board);
+        return;
+    }
+
+    if args.explain {
+        explain(&board);
+        return;
+    }
+
+    colored::control::set_override(ansi);
+
+    let game_updater_thread = live.then(|| {
+        let mut game_updater = GameUpdater::new(
+            board_rx,
+            args.throttle_ms,
+            args.pencil_marks,
+            board.box_width(),
+            board.box_height(),
         );
 
-        board_file.push(list);
+        thread::spawn(move || {
+            let _ = game_updater.listen();
+        })
+    });
+
+    let alg = args.algorithm.unwrap_or(Algorithms::CandidateElection);
+    let input = board.grid();
+
+    let limits = SolveLimits {
+        cancel: None,
+        time_limit: args.timeout_ms.map(Duration::from_millis),
+        max_actions: args.max_actions,
+    };
+    let solved = thread::spawn(move || {
+        let (outcome, portfolio) = match alg {
+            Algorithms::Portfolio => {
+                let portfolio = solve_portfolio(&mut board, &Algorithms::SOLVERS, limits);
+                (portfolio.outcome.clone(), Some(portfolio))
+            }
+            alg => (solve_with_limits(&mut board, alg, limits), None),
+        };
+        (outcome, portfolio, board)
+    })
+    .join();
+
+    let _ = board_tx.send(BoardEvent::Finished);
+    if let Some(game_updater_thread) = game_updater_thread {
+        let _ = game_updater_thread.join();
+    }
+
+    let Ok((outcome, portfolio, board)) = solved else {
+        std::process::exit(1);
+    };
+
+    // Without the live board only the final one is drawn, the other outputs
+    // leave stdout to the result.
+    if args.output == OutputFormat::Text && !live {
+        print!("{}", SudokuPrinter::from_board(&board, args.pencil_marks));
+    }
+
+    // Reported once the live board is done, so it isn't drawn over.
+    let alg = match &portfolio {
+        Some(portfolio) => {
+            report_portfolio(portfolio);
+            portfolio.winner.unwrap_or(alg)
+        }
+        None => alg,
+    };
+
+    eprintln!("{}", outcome.stats);
+
+    let failure = match outcome.status {
+        SolveStatus::Solved => None,
+        SolveStatus::Unsolvable => Some("No solution"),
+        SolveStatus::Aborted => Some("Stopped before finding a solution"),
+    };
+
+    match args.output {
+        OutputFormat::Json => println!("{}", json::solve_document(alg, &input, &outcome)),
+        // The grid as the solver left it, `?` for what it couldn't fill, is
+        // all that goes to stdout so it can be read back in.
+        OutputFormat::Plain => {
+            println!("{}", format_grid(&outcome.grid));
+            if let Some(failure) = failure {
+                eprintln!("{failure}");
+            }
+        }
+        OutputFormat::Text => {
+            if let Some(failure) = failure {
+                println!("{failure}");
+            }
+        }
+    }
+
+    if failure.is_some() {
+        std::process::exit(1);
     }
+}
+
+fn report_portfolio(portfolio: &PortfolioOutcome) {
+    match portfolio.winner {
+        Some(winner) => eprintln!("Portfolio: {winner} won"),
+        None => eprintln!("Portfolio: no algorithm finished"),
+    }
+
+    for (algorithm, outcome) in &portfolio.runs {
+        eprintln!(
+            "  {algorithm}: {} after {:.6}s, actions={}",
+            outcome.status,
+            outcome.stats.elapsed.as_secs_f64(),
+            outcome.stats.actions
+        );
+    }
+}
+
+fn report_solution_count(solution_count: SolutionCount, limit: usize) -> ! {
+    match solution_count {
+        SolutionCount::NoSolution => {
+            println!("No solution");
+            std::process::exit(1);
+        }
+        SolutionCount::Unique(grid) => {
+            println!("Unique solution");
+            println!("{}", format_grid(&grid));
+            std::process::exit(0);
+        }
+        SolutionCount::Multiple { first_two, found } => {
+            if found >= limit.max(2) {
+                println!("Multiple solutions (stopped after {found})");
+            } else {
+                println!("Multiple solutions ({found})");
+            }
+
+            for grid in &first_two {
+                println!("{}", format_grid(grid));
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+fn explain(board: &SudokuBoard) {
+    let mut solver = LogicalSolver::new(board);
+    let steps = solver.solve();
+
+    for (number, step) in steps.iter().enumerate() {
+        println!("{}. {}", number + 1, step);
+    }
+
+    if solver.is_solved() {
+        println!("Solved in {} steps", steps.len());
+    } else {
+        println!(
+            "Stuck after {} steps, no known technique applies",
+            steps.len()
+        );
+    }
+    println!("{}", format_grid(&solver.grid()));
+}
+
+fn show_hint(board: &SudokuBoard) {
+    match hint(board) {
+        Some(hint) => {
+            for step in &hint.prerequisites {
+                println!("First, {step}");
+            }
+            println!("{}", hint.step);
+        }
+        None => println!("No hint, the board is full or needs more than the known techniques"),
+    }
+}
 
-    Ok(board_file)
+fn format_grid(grid: &Grid) -> String {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&value| match value {
+                    0 => '?',
+                    value => SudokuBoard::value_to_char(value),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
@@ -1,2 +1,10 @@
-pub mod algorithms;
-pub mod board;
+pub(crate) mod algorithms;
+pub(crate) mod batch;
+pub(crate) mod board;
+pub(crate) mod candidate_grid;
+pub(crate) mod error;
+pub(crate) mod generator;
+pub(crate) mod hint;
+pub(crate) mod parser;
+pub(crate) mod rating;
+pub(crate) mod techniques;
@@ -1,4 +1,193 @@
-pub mod backtracking;
-pub mod base_algorithms;
-pub mod candidate_election;
-mod perf;
+pub(crate) mod backtracking;
+pub(crate) mod base_algorithms;
+pub(crate) mod candidate_election;
+pub(crate) mod constraint_propagation;
+pub(crate) mod dancing_links;
+pub(crate) mod limits;
+pub(crate) mod outcome;
+pub(crate) mod parallel;
+pub(crate) mod perf;
+pub(crate) mod portfolio;
+pub(crate) mod solutions;
+
+use std::fmt;
+
+use crate::sudoku::{
+    algorithms::{
+        backtracking::Backtracking, base_algorithms::BaseAlgorithms,
+        candidate_election::CandidateElection, constraint_propagation::ConstraintPropagation,
+        dancing_links::DancingLinks, limits::SolveLimits, outcome::SolveOutcome,
+        parallel::ParallelSearch, portfolio::solve_portfolio,
+    },
+    board::SudokuBoard,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithms {
+    Backtracking,
+    CandidateElection,
+    DancingLinks,
+    ConstraintPropagation,
+    /// Constraint propagation with the search tree split across threads.
+    Parallel,
+    /// Every single-threaded algorithm at once, in parallel.
+    Portfolio,
+}
+
+impl Algorithms {
+    /// The single-threaded algorithms, the ones a portfolio races.
+    pub const SOLVERS: [Algorithms; 4] = [
+        Algorithms::Backtracking,
+        Algorithms::CandidateElection,
+        Algorithms::DancingLinks,
+        Algorithms::ConstraintPropagation,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithms::Backtracking => "backtracking",
+            Algorithms::CandidateElection => "candidate election",
+            Algorithms::DancingLinks => "dancing links",
+            Algorithms::ConstraintPropagation => "constraint propagation",
+            Algorithms::Parallel => "parallel",
+            Algorithms::Portfolio => "portfolio",
+        }
+    }
+}
+
+impl fmt::Display for Algorithms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Fills `board` in place with the chosen algorithm.
+pub fn solve(board: &mut SudokuBoard, algorithm: Algorithms) -> SolveOutcome {
+    solve_with_limits(board, algorithm, SolveLimits::default())
+}
+
+/// Like `solve`, giving up with an aborted outcome once a limit is hit.
+pub fn solve_with_limits(
+    board: &mut SudokuBoard,
+    algorithm: Algorithms,
+    limits: SolveLimits,
+) -> SolveOutcome {
+    match algorithm {
+        Algorithms::Backtracking => Backtracking::new(board).resolve_with_limits(limits),
+        Algorithms::CandidateElection => CandidateElection::new(board).resolve_with_limits(limits),
+        Algorithms::DancingLinks => DancingLinks::new(board).resolve_with_limits(limits),
+        Algorithms::ConstraintPropagation => {
+            ConstraintPropagation::new(board).resolve_with_limits(limits)
+        }
+        Algorithms::Parallel => ParallelSearch::new(board).resolve_with_limits(limits),
+        Algorithms::Portfolio => solve_portfolio(board, &Algorithms::SOLVERS, limits).outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{
+        algorithms::{limits::CancellationToken, outcome::SolveStatus},
+        board::Grid,
+        parser::{Puzzle, parse},
+    };
+
+    const EXAMPLES: [&str; 4] = [
+        include_str!("../../example/6x6.txt"),
+        include_str!("../../example/easy.txt"),
+        include_str!("../../example/expert.txt"),
+        include_str!("../../example/wikipedia.txt"),
+    ];
+
+    const ALL: [Algorithms; 6] = [
+        Algorithms::Backtracking,
+        Algorithms::CandidateElection,
+        Algorithms::DancingLinks,
+        Algorithms::ConstraintPropagation,
+        Algorithms::Parallel,
+        Algorithms::Portfolio,
+    ];
+
+    /// Two 9s in the last column leave nothing for the top right cell,
+    /// without any given repeating a value.
+    const UNSOLVABLE: &str = "\
+12345678?
+????????9
+?????????
+?????????
+?????????
+?????????
+?????????
+?????????
+?????????
+";
+
+    fn is_solution(puzzle: &Puzzle, grid: &Grid) -> bool {
+        let keeps_givens = puzzle.iter().zip(grid).all(|(givens, row)| {
+            givens
+                .iter()
+                .zip(row)
+                .all(|(given, &value)| given.is_none_or(|given| given == value))
+        });
+        let filled = grid
+            .iter()
+            .map(|row| row.iter().map(|&value| Some(value)).collect())
+            .collect();
+
+        keeps_givens && SudokuBoard::new(filled).unwrap().finish().is_ok()
+    }
+
+    #[test]
+    fn every_algorithm_solves_the_examples() {
+        for text in EXAMPLES {
+            let puzzle = parse(text);
+
+            for algorithm in ALL {
+                let mut board = SudokuBoard::new(puzzle.clone()).unwrap();
+                let outcome = solve(&mut board, algorithm);
+
+                assert_eq!(outcome.status, SolveStatus::Solved, "{algorithm}");
+                assert!(is_solution(&puzzle, &outcome.grid), "{algorithm}");
+                assert_eq!(board.grid(), outcome.grid, "{algorithm}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_algorithm_reports_an_unsolvable_puzzle() {
+        for algorithm in ALL {
+            let mut board = SudokuBoard::new(parse(UNSOLVABLE)).unwrap();
+
+            assert_eq!(
+                solve(&mut board, algorithm).status,
+                SolveStatus::Unsolvable,
+                "{algorithm}"
+            );
+        }
+    }
+
+    #[test]
+    fn every_algorithm_gives_up_at_the_limits() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        for limits in [
+            SolveLimits {
+                max_actions: Some(5),
+                ..SolveLimits::default()
+            },
+            SolveLimits {
+                cancel: Some(cancel),
+                ..SolveLimits::default()
+            },
+        ] {
+            for algorithm in ALL {
+                let mut board = SudokuBoard::new(parse(EXAMPLES[2])).unwrap();
+                let outcome = solve_with_limits(&mut board, algorithm, limits.clone());
+
+                assert_eq!(outcome.status, SolveStatus::Aborted, "{algorithm}");
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::sudoku::algorithms::base_algorithms::BaseAlgorithms;
+use crate::sudoku::algorithms::limits::SolveLimits;
+use crate::sudoku::algorithms::outcome::SolveOutcome;
 use crate::sudoku::algorithms::perf::PerfTracker;
 use crate::sudoku::board::{CellType, SudokuBoard};
 
@@ -16,14 +18,16 @@ impl<'a> BaseAlgorithms<'a> for Backtracking<'a> {
         }
     }
 
-    fn resolve(self) {
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome {
         let this = self;
         let mut backtrack_index = 0usize;
-        let mut perf = PerfTracker::new();
+        let mut perf = PerfTracker::with_limits(limits);
+
+        let max_value = this.board.board_max_number() as CellType;
 
         perf.start();
 
-        while this.editable_cells.len() > backtrack_index {
+        'search: while this.editable_cells.len() > backtrack_index {
             let (mut x, mut y) = this.editable_cells[backtrack_index];
             let mut current_value = {
                 let cell = this.board.find_cell_from_coordinates(x, y).unwrap();
@@ -31,22 +35,30 @@ impl<'a> BaseAlgorithms<'a> for Backtracking<'a> {
             };
             let board = &mut *this.board;
 
-            while current_value.unwrap() <= SudokuBoard::BOARD_MAX_NUMBER as CellType {
+            while current_value.unwrap() <= max_value {
+                if perf.should_stop() {
+                    break 'search;
+                }
+
                 if Self::update_and_incr(board, &mut perf, x, y, current_value) {
                     backtrack_index += 1;
                     break;
                 } else {
-                    if current_value.unwrap() >= SudokuBoard::BOARD_MAX_NUMBER as CellType {
-                        let _ = board.update_value(x, y, None).unwrap();
+                    if current_value.unwrap() >= max_value {
+                        board.update_value(x, y, None).unwrap();
                         perf.incr();
 
+                        // Every value failed down to the first cell.
+                        if backtrack_index == 0 {
+                            break 'search;
+                        }
+
                         backtrack_index -= 1;
                         (x, y) = this.editable_cells[backtrack_index];
                         let cell = board.find_cell_from_coordinates(x, y).unwrap();
-                        current_value = if cell.value.is_some() {
-                            Some(cell.value.unwrap() + 1)
-                        } else {
-                            Some(1)
+                        current_value = match cell.value {
+                            Some(value) => Some(value + 1),
+                            None => Some(1),
                         };
                     } else {
                         current_value = Some(current_value.unwrap() + 1);
@@ -54,20 +66,18 @@ impl<'a> BaseAlgorithms<'a> for Backtracking<'a> {
                 }
             }
 
-            if current_value.unwrap() > SudokuBoard::BOARD_MAX_NUMBER as CellType {
-                let _ = board.update_value(x, y, None).unwrap();
+            if current_value.unwrap() > max_value {
+                board.update_value(x, y, None).unwrap();
                 perf.incr();
-                backtrack_index -= 1;
-            }
-        }
 
-        perf.finish();
-        let result = this.board.finish();
+                if backtrack_index == 0 {
+                    break;
+                }
 
-        if result.is_err() {
-            panic!("{:?}", result)
+                backtrack_index -= 1;
+            }
         }
 
-        perf.print_summary();
+        Self::outcome(this.board, &mut perf)
     }
 }
@@ -1,11 +1,15 @@
 use crate::sudoku::{
-    algorithms::perf::PerfTracker,
+    algorithms::{
+        limits::SolveLimits,
+        outcome::{SolveOutcome, SolveStatus},
+        perf::PerfTracker,
+    },
     board::{CellType, SudokuBoard},
 };
 
-pub trait BaseAlgorithms<'a> {
+pub trait BaseAlgorithms<'a>: Sized {
     fn new(sudoku_board: &'a mut SudokuBoard) -> Self;
-    fn resolve(self);
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome;
 
     fn update_and_incr(
         board: &mut SudokuBoard,
@@ -14,8 +18,29 @@ pub trait BaseAlgorithms<'a> {
         y: usize,
         value: Option<CellType>,
     ) -> bool {
-        let res = board.update_value(x, y, value);
+        let res = board.try_update_value(x, y, value);
         perf.incr();
-        res.is_ok()
+        res
+    }
+
+    /// Stops the clock and reports the board as solved only when it is
+    /// complete and consistent.
+    fn outcome(board: &SudokuBoard, perf: &mut PerfTracker) -> SolveOutcome {
+        perf.finish();
+
+        let status = if perf.aborted() {
+            SolveStatus::Aborted
+        } else {
+            match board.finish() {
+                Ok(()) => SolveStatus::Solved,
+                Err(_) => SolveStatus::Unsolvable,
+            }
+        };
+
+        SolveOutcome {
+            status,
+            grid: board.grid(),
+            stats: perf.stats(),
+        }
     }
 }
@@ -1,5 +1,8 @@
 use crate::sudoku::{
-    algorithms::{base_algorithms::BaseAlgorithms, perf::PerfTracker},
+    algorithms::{
+        base_algorithms::BaseAlgorithms, limits::SolveLimits, outcome::SolveOutcome,
+        perf::PerfTracker,
+    },
     board::{CellType, SudokuBoard},
 };
 
@@ -20,10 +23,8 @@ impl<'a> BaseAlgorithms<'a> for CandidateElection<'a> {
         let mut editable_cells = Vec::with_capacity(cells.len());
 
         for (x, y) in cells {
-            let candidates: Vec<CellType> = SudokuBoard::valid_values()
-                .into_iter()
-                .filter(|&v| sudoku_board.is_valid_insertion(x, y, Some(v as CellType)))
-                .collect();
+            let candidates: Vec<CellType> =
+                SudokuBoard::mask_values(sudoku_board.candidates(x, y)).collect();
 
             editable_cells.push(EditableCells { candidates, x, y });
         }
@@ -34,14 +35,14 @@ impl<'a> BaseAlgorithms<'a> for CandidateElection<'a> {
         }
     }
 
-    fn resolve(self) {
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome {
         let this = self;
         let mut backtrack_index = 0usize;
-        let mut perf = PerfTracker::new();
+        let mut perf = PerfTracker::with_limits(limits);
 
         perf.start();
 
-        while this.editable_cells.len() > backtrack_index {
+        'search: while this.editable_cells.len() > backtrack_index {
             let (mut index, mut x, mut y, mut candidate_len) = {
                 let ec = &this.editable_cells[backtrack_index];
                 let (x, y) = (ec.x, ec.y);
@@ -62,6 +63,10 @@ impl<'a> BaseAlgorithms<'a> for CandidateElection<'a> {
             let board = &mut *this.board;
 
             while index < candidate_len {
+                if perf.should_stop() {
+                    break 'search;
+                }
+
                 let value: u16 = this.editable_cells[backtrack_index].candidates[index];
 
                 if Self::update_and_incr(board, &mut perf, x, y, Some(value)) {
@@ -71,6 +76,11 @@ impl<'a> BaseAlgorithms<'a> for CandidateElection<'a> {
                     if index >= candidate_len {
                         let _ = Self::update_and_incr(board, &mut perf, x, y, None);
 
+                        // Every candidate failed down to the first cell.
+                        if backtrack_index == 0 {
+                            break 'search;
+                        }
+
                         backtrack_index -= 1;
                         (x, y, candidate_len) = {
                             let ec = &this.editable_cells[backtrack_index];
@@ -94,19 +104,17 @@ impl<'a> BaseAlgorithms<'a> for CandidateElection<'a> {
             }
 
             if index >= candidate_len {
-                let _ = this.board.update_value(x, y, None).unwrap();
+                this.board.update_value(x, y, None).unwrap();
                 perf.incr();
-                backtrack_index -= 1;
-            }
-        }
 
-        perf.finish();
-        let result = this.board.finish();
+                if backtrack_index == 0 {
+                    break;
+                }
 
-        if result.is_err() {
-            panic!("{:?}", result)
+                backtrack_index -= 1;
+            }
         }
 
-        perf.print_summary();
+        Self::outcome(this.board, &mut perf)
     }
 }
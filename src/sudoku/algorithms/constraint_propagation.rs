@@ -0,0 +1,195 @@
+use crate::sudoku::{
+    algorithms::{
+        base_algorithms::BaseAlgorithms, limits::SolveLimits, outcome::SolveOutcome,
+        perf::PerfTracker,
+    },
+    board::{CandidateMask, CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+};
+
+/// Keeps the candidates of every cell up to date as values are placed, fills
+/// naked and hidden singles until nothing changes and only then guesses,
+/// always on the cell with the fewest remaining candidates.
+pub struct ConstraintPropagation<'a> {
+    board: &'a mut SudokuBoard,
+    grid: CandidateGrid,
+}
+
+impl ConstraintPropagation<'_> {
+    /// Returns `false` when a cell runs out of candidates or a value has no
+    /// place left in one of its units.
+    fn propagate(grid: &mut CandidateGrid, perf: &mut PerfTracker) -> bool {
+        loop {
+            let mut changed = false;
+
+            for index in 0..grid.cell_count() {
+                if grid.value(index).is_some() {
+                    continue;
+                }
+
+                let candidates = grid.candidates(index);
+                if candidates == 0 {
+                    return false;
+                }
+
+                if candidates.count_ones() == 1 {
+                    grid.place(index, candidates.trailing_zeros() as CellType + 1);
+                    perf.incr();
+                    changed = true;
+                }
+            }
+
+            let full_mask = grid.full_mask();
+            for unit in 0..grid.units().len() {
+                let mut placed: CandidateMask = 0;
+                let mut once: CandidateMask = 0;
+                let mut twice: CandidateMask = 0;
+
+                for &index in &grid.units()[unit] {
+                    let candidates = grid.candidates(index);
+
+                    if grid.value(index).is_some() {
+                        placed |= candidates;
+                    } else {
+                        twice |= once & candidates;
+                        once |= candidates;
+                    }
+                }
+
+                if (placed | once) != full_mask {
+                    return false;
+                }
+
+                let hidden_singles = once & !twice & !placed;
+                for value in SudokuBoard::mask_values(hidden_singles) {
+                    let bit = SudokuBoard::value_mask(value);
+                    let cell = grid.units()[unit].iter().copied().find(|&index| {
+                        grid.value(index).is_none() && grid.candidates(index) & bit != 0
+                    });
+
+                    // An earlier single in this unit may already have taken
+                    // the only cell left for this value.
+                    match cell {
+                        Some(index) => {
+                            grid.place(index, value);
+                            perf.incr();
+                            changed = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Walks every solution reachable from `grid`, handing each one to
+    /// `on_solution` until it returns `true` to stop the search. Returns
+    /// whether the search was stopped, a limit hit on `perf` unwinds it
+    /// without stopping.
+    pub fn search(
+        mut grid: CandidateGrid,
+        perf: &mut PerfTracker,
+        on_solution: &mut dyn FnMut(CandidateGrid) -> bool,
+    ) -> bool {
+        if !Self::propagate(&mut grid, perf) {
+            return false;
+        }
+
+        let branch_cell = (0..grid.cell_count())
+            .filter(|&index| grid.value(index).is_none())
+            .min_by_key(|&index| grid.candidates(index).count_ones());
+
+        let index = match branch_cell {
+            Some(index) => index,
+            None => return on_solution(grid),
+        };
+
+        for value in SudokuBoard::mask_values(grid.candidates(index)) {
+            if perf.should_stop() {
+                return false;
+            }
+
+            let mut guess = grid.clone();
+            guess.place(index, value);
+            perf.incr();
+            perf.branch();
+
+            if Self::search(guess, perf, on_solution) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl<'a> BaseAlgorithms<'a> for ConstraintPropagation<'a> {
+    fn new(sudoku_board: &'a mut SudokuBoard) -> Self {
+        let grid = CandidateGrid::from_board(sudoku_board);
+
+        ConstraintPropagation {
+            board: sudoku_board,
+            grid,
+        }
+    }
+
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome {
+        let this = self;
+        let mut perf = PerfTracker::with_limits(limits);
+
+        perf.start();
+
+        let mut first_solution = None;
+        Self::search(this.grid, &mut perf, &mut |solution| {
+            first_solution = Some(solution);
+            true
+        });
+
+        if let Some(solution) = first_solution {
+            for (x, y) in this.board.get_editable_cells() {
+                let value = solution.value(solution.index(x, y));
+                Self::update_and_incr(this.board, &mut perf, x, y, value);
+            }
+        }
+
+        Self::outcome(this.board, &mut perf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::algorithms::outcome::SolveStatus;
+
+    #[test]
+    fn search_visits_every_solution_until_told_to_stop() {
+        let board = SudokuBoard::new(vec![vec![None; 4]; 4]).unwrap();
+        let mut found = 0;
+
+        ConstraintPropagation::search(
+            CandidateGrid::from_board(&board),
+            &mut PerfTracker::new(),
+            &mut |solution| {
+                assert!(solution.is_solved());
+                found += 1;
+                false
+            },
+        );
+
+        assert_eq!(found, 288);
+    }
+
+    #[test]
+    fn solves_boxes_taller_than_wide() {
+        let mut board = SudokuBoard::with_box_size(vec![vec![None; 6]; 6], 2, 3).unwrap();
+        let outcome =
+            ConstraintPropagation::new(&mut board).resolve_with_limits(SolveLimits::default());
+
+        assert_eq!(outcome.status, SolveStatus::Solved);
+        assert!(board.finish().is_ok());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::sudoku::{
+    algorithms::{
+        base_algorithms::BaseAlgorithms, limits::SolveLimits, outcome::SolveOutcome,
+        perf::PerfTracker,
+    },
+    board::{CellType, SudokuBoard},
+};
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    left: usize,
+    right: usize,
+    up: usize,
+    down: usize,
+    column: usize,
+    row: usize,
+}
+
+/// Knuth's Algorithm X over a toroidal doubly linked list. Nodes live in a
+/// single vector and link to each other by index; the root is node 0 and the
+/// column headers follow it.
+struct Links {
+    nodes: Vec<Node>,
+    sizes: Vec<usize>,
+}
+
+impl Links {
+    fn new(columns: usize) -> Self {
+        let mut nodes = Vec::with_capacity(columns + 1);
+
+        for index in 0..=columns {
+            nodes.push(Node {
+                left: if index == 0 { columns } else { index - 1 },
+                right: if index == columns { 0 } else { index + 1 },
+                up: index,
+                down: index,
+                column: index,
+                row: usize::MAX,
+            });
+        }
+
+        Links {
+            nodes,
+            sizes: vec![0; columns + 1],
+        }
+    }
+
+    fn add_row(&mut self, row: usize, columns: &[usize]) {
+        let first = self.nodes.len();
+
+        for (offset, &column) in columns.iter().enumerate() {
+            let index = first + offset;
+            let up = self.nodes[column].up;
+
+            self.nodes.push(Node {
+                left: if offset == 0 {
+                    first + columns.len() - 1
+                } else {
+                    index - 1
+                },
+                right: if offset == columns.len() - 1 {
+                    first
+                } else {
+                    index + 1
+                },
+                up,
+                down: column,
+                column,
+                row,
+            });
+            self.nodes[up].down = index;
+            self.nodes[column].up = index;
+            self.sizes[column] += 1;
+        }
+    }
+
+    fn cover(&mut self, column: usize) {
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+
+        let mut i = self.nodes[column].down;
+        while i != column {
+            let mut j = self.nodes[i].right;
+            while j != i {
+                let Node { up, down, .. } = self.nodes[j];
+                self.nodes[up].down = down;
+                self.nodes[down].up = up;
+                self.sizes[self.nodes[j].column] -= 1;
+                j = self.nodes[j].right;
+            }
+            i = self.nodes[i].down;
+        }
+    }
+
+    fn uncover(&mut self, column: usize) {
+        let mut i = self.nodes[column].up;
+        while i != column {
+            let mut j = self.nodes[i].left;
+            while j != i {
+                let Node { up, down, .. } = self.nodes[j];
+                self.nodes[up].down = j;
+                self.nodes[down].up = j;
+                self.sizes[self.nodes[j].column] += 1;
+                j = self.nodes[j].left;
+            }
+            i = self.nodes[i].up;
+        }
+
+        let Node { left, right, .. } = self.nodes[column];
+        self.nodes[left].right = column;
+        self.nodes[right].left = column;
+    }
+
+    fn smallest_column(&self) -> Option<usize> {
+        let mut column = self.nodes[ROOT].right;
+        let mut smallest: Option<usize> = None;
+
+        while column != ROOT {
+            if smallest.is_none_or(|smallest| self.sizes[column] < self.sizes[smallest]) {
+                smallest = Some(column);
+            }
+            column = self.nodes[column].right;
+        }
+
+        smallest
+    }
+
+    fn search(&mut self, solution: &mut Vec<usize>, perf: &mut PerfTracker) -> bool {
+        let column = match self.smallest_column() {
+            Some(column) => column,
+            None => return true,
+        };
+
+        if self.sizes[column] == 0 {
+            return false;
+        }
+
+        self.cover(column);
+
+        let mut r = self.nodes[column].down;
+        while r != column && !perf.should_stop() {
+            solution.push(self.nodes[r].row);
+            perf.incr();
+
+            let mut j = self.nodes[r].right;
+            while j != r {
+                self.cover(self.nodes[j].column);
+                j = self.nodes[j].right;
+            }
+
+            if self.search(solution, perf) {
+                return true;
+            }
+
+            let mut j = self.nodes[r].left;
+            while j != r {
+                self.uncover(self.nodes[j].column);
+                j = self.nodes[j].left;
+            }
+
+            solution.pop();
+            r = self.nodes[r].down;
+        }
+
+        self.uncover(column);
+
+        false
+    }
+}
+
+/// Solves the board as an exact cover problem. Every empty cell and every
+/// value missing from a row, column or box is a constraint (324 of them on
+/// an empty 9x9 board) and every candidate placement is a row covering four
+/// of them.
+pub struct DancingLinks<'a> {
+    board: &'a mut SudokuBoard,
+    links: Links,
+    placements: Vec<(usize, usize, CellType)>,
+}
+
+impl<'a> BaseAlgorithms<'a> for DancingLinks<'a> {
+    fn new(sudoku_board: &'a mut SudokuBoard) -> Self {
+        let board_max_number = sudoku_board.board_max_number();
+        let cells = board_max_number * board_max_number;
+        let constraint_index = |x: usize, y: usize, value: CellType| {
+            let value_index = value as usize - 1;
+            let box_index = sudoku_board.box_index(x, y);
+
+            [
+                x * board_max_number + y,
+                cells + x * board_max_number + value_index,
+                cells * 2 + y * board_max_number + value_index,
+                cells * 3 + box_index * board_max_number + value_index,
+            ]
+        };
+
+        // Constraints already satisfied by the givens get no column at all,
+        // the remaining ones are numbered from 1 as column headers.
+        let mut satisfied = vec![false; cells * 4];
+        let editable_cells = sudoku_board.get_editable_cells();
+
+        for x in 0..board_max_number {
+            for y in 0..board_max_number {
+                if let Ok(cell) = sudoku_board.find_cell_from_coordinates(x, y)
+                    && let Some(value) = cell.value
+                {
+                    for constraint in constraint_index(x, y, value) {
+                        satisfied[constraint] = true;
+                    }
+                }
+            }
+        }
+
+        let mut columns = vec![ROOT; cells * 4];
+        let mut column_count = 0;
+        for (constraint, column) in columns.iter_mut().enumerate() {
+            if !satisfied[constraint] {
+                column_count += 1;
+                *column = column_count;
+            }
+        }
+
+        let mut links = Links::new(column_count);
+        let mut placements = Vec::new();
+
+        for (x, y) in editable_cells {
+            for value in SudokuBoard::mask_values(sudoku_board.candidates(x, y)) {
+                let row_columns = constraint_index(x, y, value).map(|index| columns[index]);
+
+                links.add_row(placements.len(), &row_columns);
+                placements.push((x, y, value));
+            }
+        }
+
+        DancingLinks {
+            board: sudoku_board,
+            links,
+            placements,
+        }
+    }
+
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome {
+        let mut this = self;
+        let mut perf = PerfTracker::with_limits(limits);
+        let mut solution = Vec::new();
+
+        perf.start();
+
+        if this.links.search(&mut solution, &mut perf) {
+            for row in solution {
+                let (x, y, value) = this.placements[row];
+                Self::update_and_incr(this.board, &mut perf, x, y, Some(value));
+            }
+        }
+
+        Self::outcome(this.board, &mut perf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::algorithms::outcome::SolveStatus;
+
+    #[test]
+    fn finds_the_exact_cover_of_knuths_example() {
+        let mut links = Links::new(7);
+        for (row, columns) in [
+            &[3, 5, 6][..],
+            &[1, 4, 7],
+            &[2, 3, 6],
+            &[1, 4],
+            &[2, 7],
+            &[4, 5, 7],
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            links.add_row(row, columns);
+        }
+
+        let mut solution = Vec::new();
+        assert!(links.search(&mut solution, &mut PerfTracker::new()));
+
+        solution.sort_unstable();
+        assert_eq!(solution, [0, 3, 4]);
+    }
+
+    #[test]
+    fn solves_boxes_taller_than_wide() {
+        let mut board = SudokuBoard::with_box_size(vec![vec![None; 6]; 6], 2, 3).unwrap();
+        let outcome = DancingLinks::new(&mut board).resolve_with_limits(SolveLimits::default());
+
+        assert_eq!(outcome.status, SolveStatus::Solved);
+        assert!(board.finish().is_ok());
+    }
+}
@@ -0,0 +1,55 @@
+use std::{fmt, time::Duration};
+
+use crate::sudoku::board::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStatus {
+    Solved,
+    /// Every possibility was tried, the puzzle has no solution.
+    Unsolvable,
+    /// The solver stopped before it could tell.
+    Aborted,
+}
+
+impl SolveStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            SolveStatus::Solved => "solved",
+            SolveStatus::Unsolvable => "unsolvable",
+            SolveStatus::Aborted => "aborted",
+        }
+    }
+}
+
+impl fmt::Display for SolveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SolveStats {
+    pub actions: u64,
+    pub branches: u64,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Perf: actions={} elapsed={:.6}s",
+            self.actions,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+/// What a solver leaves behind: `grid` is the board as it was when the
+/// solver stopped, `0` standing for the cells it couldn't fill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveOutcome {
+    pub status: SolveStatus,
+    pub grid: Grid,
+    pub stats: SolveStats,
+}
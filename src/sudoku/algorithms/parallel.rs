@@ -0,0 +1,241 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Instant,
+};
+
+use crate::sudoku::{
+    algorithms::{
+        base_algorithms::BaseAlgorithms,
+        constraint_propagation::ConstraintPropagation,
+        limits::{CancellationToken, SolveLimits},
+        outcome::{SolveOutcome, SolveStats, SolveStatus},
+        perf::PerfTracker,
+    },
+    board::{CandidateMask, Grid, SudokuBoard},
+    candidate_grid::CandidateGrid,
+};
+
+/// Subtrees handed out per worker, so a worker that lands on an easy
+/// subtree picks up another one instead of idling.
+const TASKS_PER_WORKER: usize = 8;
+/// Guesses made up front at most, each one multiplies the subtrees.
+const MAX_SPLIT_DEPTH: usize = 4;
+
+/// Splits the search tree at the most constrained cells into subtrees and
+/// searches them on a pool of threads with constraint propagation.
+pub struct ParallelSearch<'a> {
+    board: &'a mut SudokuBoard,
+    workers: usize,
+}
+
+/// What the workers did together: `aborted` is set when a limit cut the
+/// search short, not when `on_solution` asked to stop.
+pub struct ParallelRun {
+    pub stats: SolveStats,
+    pub aborted: bool,
+}
+
+impl ParallelSearch<'_> {
+    fn most_constrained_cell(board: &SudokuBoard) -> Option<(usize, usize, CandidateMask)> {
+        board
+            .get_editable_cells()
+            .into_iter()
+            .filter(|&(x, y)| {
+                board
+                    .find_cell_from_coordinates(x, y)
+                    .is_ok_and(|cell| cell.value.is_none())
+            })
+            .map(|(x, y)| (x, y, board.candidates(x, y)))
+            .min_by_key(|(_, _, candidates)| candidates.count_ones())
+    }
+
+    /// One board per subtree, each with the guesses of its branch placed.
+    /// Branches whose guess conflicts right away are dropped.
+    fn split(board: &SudokuBoard, target: usize) -> Vec<SudokuBoard> {
+        let mut tasks = vec![board.clone().without_events()];
+
+        for _ in 0..MAX_SPLIT_DEPTH {
+            if tasks.len() >= target {
+                break;
+            }
+
+            let mut next = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                match Self::most_constrained_cell(&task) {
+                    Some((x, y, candidates)) => {
+                        for value in SudokuBoard::mask_values(candidates) {
+                            let mut branch = task.clone();
+                            if branch.update_value(x, y, Some(value)).is_ok() {
+                                next.push(branch);
+                            }
+                        }
+                    }
+                    None => next.push(task),
+                }
+            }
+
+            tasks = next;
+        }
+
+        tasks
+    }
+
+    /// Hands every solution of `board` to `on_solution` from whichever
+    /// worker finds it, until it returns `true`. Solutions come in no
+    /// particular order.
+    pub fn search(
+        board: &SudokuBoard,
+        workers: usize,
+        limits: SolveLimits,
+        on_solution: &(dyn Fn(CandidateGrid) -> bool + Sync),
+    ) -> ParallelRun {
+        let start = Instant::now();
+        let workers = workers.max(1);
+        let stop = match &limits.cancel {
+            Some(cancel) => cancel.child(),
+            None => CancellationToken::new(),
+        };
+        let stopped = AtomicBool::new(false);
+        let tasks = Mutex::new(Self::split(board, workers * TASKS_PER_WORKER));
+
+        let trackers: Vec<PerfTracker> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    let mut perf = PerfTracker::with_limits(SolveLimits {
+                        cancel: Some(stop.clone()),
+                        ..limits.clone()
+                    });
+                    let (tasks, stop, stopped) = (&tasks, &stop, &stopped);
+
+                    scope.spawn(move || {
+                        perf.start();
+
+                        while !perf.should_stop() {
+                            let Some(task) = tasks.lock().unwrap().pop() else {
+                                break;
+                            };
+
+                            ConstraintPropagation::search(
+                                CandidateGrid::from_board(&task),
+                                &mut perf,
+                                &mut |solution| {
+                                    if on_solution(solution) {
+                                        stopped.store(true, Ordering::Relaxed);
+                                        stop.cancel();
+                                    }
+
+                                    stop.is_cancelled()
+                                },
+                            );
+                        }
+
+                        perf.finish();
+                        perf
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok())
+                .collect()
+        });
+
+        let stopped = stopped.load(Ordering::Relaxed);
+
+        ParallelRun {
+            stats: SolveStats {
+                actions: trackers.iter().map(|perf| perf.stats().actions).sum(),
+                branches: trackers.iter().map(|perf| perf.stats().branches).sum(),
+                elapsed: start.elapsed(),
+            },
+            aborted: !stopped && trackers.iter().any(PerfTracker::aborted),
+        }
+    }
+}
+
+impl<'a> BaseAlgorithms<'a> for ParallelSearch<'a> {
+    fn new(sudoku_board: &'a mut SudokuBoard) -> Self {
+        ParallelSearch {
+            board: sudoku_board,
+            workers: thread::available_parallelism().map_or(1, |workers| workers.get()),
+        }
+    }
+
+    fn resolve_with_limits(self, limits: SolveLimits) -> SolveOutcome {
+        let solution: Mutex<Option<Grid>> = Mutex::new(None);
+        let run = Self::search(self.board, self.workers, limits, &|grid| {
+            solution.lock().unwrap().get_or_insert_with(|| grid.grid());
+            true
+        });
+
+        if let Some(solution) = solution.into_inner().unwrap() {
+            for (x, y) in self.board.get_editable_cells() {
+                let _ = self.board.update_value(x, y, Some(solution[x][y]));
+            }
+        }
+
+        let status = if run.aborted {
+            SolveStatus::Aborted
+        } else {
+            match self.board.finish() {
+                Ok(()) => SolveStatus::Solved,
+                Err(_) => SolveStatus::Unsolvable,
+            }
+        };
+
+        SolveOutcome {
+            status,
+            grid: self.board.grid(),
+            stats: run.stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    #[test]
+    fn workers_share_the_solutions_between_them() {
+        let board = SudokuBoard::new(vec![vec![None; 4]; 4]).unwrap();
+        let found = AtomicUsize::new(0);
+
+        let run = ParallelSearch::search(&board, 4, SolveLimits::default(), &|solution| {
+            assert!(solution.is_solved());
+            found.fetch_add(1, Ordering::Relaxed);
+            false
+        });
+
+        assert_eq!(found.into_inner(), 288);
+        assert!(!run.aborted);
+    }
+
+    #[test]
+    fn stopping_on_a_solution_is_not_an_abort() {
+        let board = SudokuBoard::new(vec![vec![None; 9]; 9]).unwrap();
+        let run = ParallelSearch::search(&board, 4, SolveLimits::default(), &|_| true);
+
+        assert!(!run.aborted);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let run = ParallelSearch::search(
+            &board,
+            4,
+            SolveLimits {
+                cancel: Some(cancel),
+                ..SolveLimits::default()
+            },
+            &|_| false,
+        );
+
+        assert!(run.aborted);
+    }
+}
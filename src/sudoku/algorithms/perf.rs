@@ -1,22 +1,36 @@
 use std::time::{Duration, Instant};
 
+use crate::sudoku::algorithms::{
+    limits::{CancellationToken, SolveLimits},
+    outcome::SolveStats,
+};
+
+#[derive(Debug, Default)]
 pub struct PerfTracker {
     actions: u64,
+    branches: u64,
     start: Option<Instant>,
     end: Option<Instant>,
+    limits: SolveLimits,
+    aborted: bool,
 }
 
 impl PerfTracker {
     pub fn new() -> Self {
-        Self {
-            actions: 0,
-            start: None,
-            end: None,
+        Self::default()
+    }
+
+    pub fn with_limits(limits: SolveLimits) -> Self {
+        PerfTracker {
+            limits,
+            ..Self::default()
         }
     }
 
     pub fn start(&mut self) {
         self.actions = 0;
+        self.branches = 0;
+        self.aborted = false;
         self.start = Some(Instant::now());
         self.end = None;
     }
@@ -25,6 +39,40 @@ impl PerfTracker {
         self.actions = self.actions.saturating_add(1);
     }
 
+    /// Counts a guess, an action taken without being sure it is right.
+    pub fn branch(&mut self) {
+        self.branches = self.branches.saturating_add(1);
+    }
+
+    pub fn branches(&self) -> u64 {
+        self.branches
+    }
+
+    /// Checked by the solvers between actions. Once a limit is hit it keeps
+    /// returning `true` so every level of a recursive search unwinds.
+    pub fn should_stop(&mut self) -> bool {
+        if !self.aborted {
+            self.aborted = self
+                .limits
+                .max_actions
+                .is_some_and(|max_actions| self.actions >= max_actions)
+                || self
+                    .limits
+                    .cancel
+                    .as_ref()
+                    .is_some_and(CancellationToken::is_cancelled)
+                || self.limits.time_limit.is_some_and(|time_limit| {
+                    self.elapsed().is_some_and(|elapsed| elapsed >= time_limit)
+                });
+        }
+
+        self.aborted
+    }
+
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
     pub fn finish(&mut self) {
         self.end = Some(Instant::now());
     }
@@ -39,12 +87,54 @@ impl PerfTracker {
         }
     }
 
-    pub fn print_summary(&self) {
-        let elapsed_str = match self.elapsed() {
-            Some(d) => format!("{:.6}s", d.as_secs_f64()),
-            None => "not started".to_string(),
-        };
+    pub fn stats(&self) -> SolveStats {
+        SolveStats {
+            actions: self.actions,
+            branches: self.branches,
+            elapsed: self.elapsed().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_once_a_limit_is_hit_and_stays_stopped() {
+        let mut perf = PerfTracker::with_limits(SolveLimits {
+            max_actions: Some(2),
+            ..SolveLimits::default()
+        });
+        perf.start();
+
+        perf.incr();
+        assert!(!perf.should_stop());
+        perf.incr();
+        assert!(perf.should_stop());
+
+        perf.start();
+        assert!(!perf.aborted());
+
+        let mut perf = PerfTracker::with_limits(SolveLimits {
+            time_limit: Some(Duration::ZERO),
+            ..SolveLimits::default()
+        });
+        assert!(!perf.should_stop(), "the clock only runs once started");
+        perf.start();
+        assert!(perf.should_stop());
+    }
+
+    #[test]
+    fn never_stops_without_limits() {
+        let mut perf = PerfTracker::new();
+        perf.start();
+
+        for _ in 0..1000 {
+            perf.incr();
+        }
 
-        eprintln!("Perf: actions={} elapsed={}", self.actions, elapsed_str);
+        assert!(!perf.should_stop());
+        assert_eq!(perf.stats().actions, 1000);
     }
 }
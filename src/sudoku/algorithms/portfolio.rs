@@ -0,0 +1,96 @@
+use std::{sync::mpsc, thread};
+
+use crate::sudoku::{
+    algorithms::{
+        Algorithms,
+        limits::{CancellationToken, SolveLimits},
+        outcome::{SolveOutcome, SolveStatus},
+        solve_with_limits,
+    },
+    board::SudokuBoard,
+};
+
+pub struct PortfolioOutcome {
+    /// The first algorithm to solve the puzzle or prove it unsolvable,
+    /// `None` when every one of them was aborted.
+    pub winner: Option<Algorithms>,
+    pub outcome: SolveOutcome,
+    /// Every algorithm with its own outcome, in the order they stopped.
+    pub runs: Vec<(Algorithms, SolveOutcome)>,
+}
+
+/// Races `algorithms` on copies of `board`, each in its own thread, and
+/// cancels the rest as soon as one of them gets an answer. The winner's
+/// values are written back to `board`.
+pub fn solve_portfolio(
+    board: &mut SudokuBoard,
+    algorithms: &[Algorithms],
+    limits: SolveLimits,
+) -> PortfolioOutcome {
+    let cancel = match &limits.cancel {
+        Some(cancel) => cancel.child(),
+        None => CancellationToken::new(),
+    };
+    let (outcome_tx, outcome_rx) = mpsc::channel();
+    let mut runs: Vec<(Algorithms, SolveOutcome)> = Vec::with_capacity(algorithms.len());
+    let mut winner: Option<Algorithms> = None;
+
+    thread::scope(|scope| {
+        for &algorithm in algorithms {
+            let mut copy = board.clone().without_events();
+            let limits = SolveLimits {
+                cancel: Some(cancel.clone()),
+                ..limits.clone()
+            };
+            let outcome_tx = outcome_tx.clone();
+
+            scope.spawn(move || {
+                let outcome = solve_with_limits(&mut copy, algorithm, limits);
+                let _ = outcome_tx.send((algorithm, outcome));
+            });
+        }
+
+        for (algorithm, outcome) in outcome_rx.iter().take(algorithms.len()) {
+            if winner.is_none() && outcome.status != SolveStatus::Aborted {
+                winner = Some(algorithm);
+                cancel.cancel();
+            }
+
+            runs.push((algorithm, outcome));
+        }
+    });
+
+    let outcome = match winner.and_then(|winner| runs.iter().find(|(run, _)| *run == winner)) {
+        Some((_, outcome)) => {
+            write_back(board, outcome);
+            outcome.clone()
+        }
+        None => SolveOutcome {
+            status: SolveStatus::Aborted,
+            grid: board.grid(),
+            stats: runs
+                .iter()
+                .map(|(_, outcome)| outcome.stats)
+                .max_by_key(|stats| stats.elapsed)
+                .unwrap_or_default(),
+        },
+    };
+
+    PortfolioOutcome {
+        winner,
+        outcome,
+        runs,
+    }
+}
+
+fn write_back(board: &mut SudokuBoard, outcome: &SolveOutcome) {
+    if outcome.status != SolveStatus::Solved {
+        return;
+    }
+
+    for (x, y) in board.get_editable_cells() {
+        let _ = board.update_value(x, y, Some(outcome.grid[x][y]));
+    }
+
+    let _ = board.finish();
+}
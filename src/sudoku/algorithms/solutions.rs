@@ -0,0 +1,152 @@
+use std::{sync::Mutex, thread};
+
+use crate::sudoku::{
+    algorithms::{
+        constraint_propagation::ConstraintPropagation, limits::SolveLimits,
+        parallel::ParallelSearch, perf::PerfTracker,
+    },
+    board::{Grid, SudokuBoard},
+    candidate_grid::CandidateGrid,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolutionCount {
+    NoSolution,
+    Unique(Grid),
+    /// `found` counts the solutions enumerated, capped at the search limit.
+    Multiple {
+        first_two: [Grid; 2],
+        found: usize,
+    },
+}
+
+/// Enumerates the solutions of `board` without touching it, stopping once
+/// `limit` solutions have been found. A limit below 2 is raised to 2, the
+/// least needed to tell a unique puzzle from an ambiguous one.
+pub fn count_solutions(board: &SudokuBoard, limit: usize) -> SolutionCount {
+    let limit = limit.max(2);
+    let mut perf = PerfTracker::new();
+    let mut first_two: Vec<Grid> = Vec::with_capacity(2);
+    let mut found = 0usize;
+
+    ConstraintPropagation::search(
+        CandidateGrid::from_board(board),
+        &mut perf,
+        &mut |solution| {
+            found += 1;
+            if first_two.len() < 2 {
+                first_two.push(solution.grid());
+            }
+
+            found >= limit
+        },
+    );
+
+    tally(first_two, found)
+}
+
+/// Same as `count_solutions`, with the search split across every core.
+pub fn count_solutions_parallel(board: &SudokuBoard, limit: usize) -> SolutionCount {
+    let limit = limit.max(2);
+    let tally_so_far: Mutex<(Vec<Grid>, usize)> = Mutex::new((Vec::with_capacity(2), 0));
+    let workers = thread::available_parallelism().map_or(1, |workers| workers.get());
+
+    ParallelSearch::search(board, workers, SolveLimits::default(), &|solution| {
+        let mut tally_so_far = tally_so_far.lock().unwrap();
+        let (first_two, found) = &mut *tally_so_far;
+
+        // Workers may find more before they notice the search is over.
+        if *found >= limit {
+            return true;
+        }
+
+        *found += 1;
+        if first_two.len() < 2 {
+            first_two.push(solution.grid());
+        }
+
+        *found >= limit
+    });
+
+    let (first_two, found) = tally_so_far.into_inner().unwrap();
+    tally(first_two, found)
+}
+
+fn tally(first_two: Vec<Grid>, found: usize) -> SolutionCount {
+    let mut solutions = first_two.into_iter();
+
+    match (solutions.next(), solutions.next()) {
+        (None, _) => SolutionCount::NoSolution,
+        (Some(first), None) => SolutionCount::Unique(first),
+        (Some(first), Some(second)) => SolutionCount::Multiple {
+            first_two: [first, second],
+            found,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{
+        algorithms::{Algorithms, solve},
+        parser::parse,
+    };
+
+    fn board(text: &str) -> SudokuBoard {
+        SudokuBoard::new(parse(text)).unwrap()
+    }
+
+    #[test]
+    fn a_proper_puzzle_has_its_solution_counted_once() {
+        let board = board(include_str!("../../../example/expert.txt"));
+        let solution = solve(&mut board.clone(), Algorithms::DancingLinks).grid;
+
+        assert_eq!(
+            count_solutions(&board, 10),
+            SolutionCount::Unique(solution.clone())
+        );
+        assert_eq!(
+            count_solutions_parallel(&board, 10),
+            SolutionCount::Unique(solution)
+        );
+    }
+
+    #[test]
+    fn counting_stops_at_the_limit() {
+        // An empty 4x4 board has 288 solutions.
+        let board = board("????\n????\n????\n????\n");
+
+        for count in [
+            count_solutions(&board, 10),
+            count_solutions_parallel(&board, 10),
+        ] {
+            let SolutionCount::Multiple { first_two, found } = count else {
+                panic!("expected several solutions, got {count:?}");
+            };
+
+            assert_eq!(found, 10);
+            assert_ne!(first_two[0], first_two[1]);
+        }
+
+        assert!(matches!(
+            count_solutions(&board, 0),
+            SolutionCount::Multiple { found: 2, .. }
+        ));
+        assert!(matches!(
+            count_solutions(&board, 1000),
+            SolutionCount::Multiple { found: 288, .. }
+        ));
+    }
+
+    #[test]
+    fn an_unsolvable_puzzle_has_no_solution() {
+        let board = board("12??\n??3?\n???4\n????\n");
+
+        assert_eq!(count_solutions(&board, 2), SolutionCount::NoSolution);
+        assert_eq!(
+            count_solutions_parallel(&board, 2),
+            SolutionCount::NoSolution
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{fmt, time::Duration};
+
+use crate::sudoku::algorithms::outcome::{SolveOutcome, SolveStatus};
+
+/// Running totals over many solves. Keeps every time and action count to
+/// report percentiles.
+#[derive(Debug, Clone, Default)]
+pub struct BatchStats {
+    pub solved: usize,
+    pub unsolvable: usize,
+    pub aborted: usize,
+    /// Puzzles that couldn't be read or loaded, never handed to a solver.
+    pub invalid: usize,
+    times: Vec<Duration>,
+    actions: Vec<u64>,
+}
+
+impl BatchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, outcome: &SolveOutcome) {
+        match outcome.status {
+            SolveStatus::Solved => self.solved += 1,
+            SolveStatus::Unsolvable => self.unsolvable += 1,
+            SolveStatus::Aborted => self.aborted += 1,
+        }
+
+        self.times.push(outcome.stats.elapsed);
+        self.actions.push(outcome.stats.actions);
+    }
+
+    pub fn record_invalid(&mut self) {
+        self.invalid += 1;
+    }
+
+    pub fn total(&self) -> usize {
+        self.solved + self.unsolvable + self.aborted + self.invalid
+    }
+
+    pub fn mean_time(&self) -> Duration {
+        match self.times.len() {
+            0 => Duration::ZERO,
+            count => self.times.iter().sum::<Duration>() / count as u32,
+        }
+    }
+
+    pub fn time_percentile(&self, percentile: f64) -> Duration {
+        nearest_rank(&self.times, percentile).unwrap_or_default()
+    }
+
+    pub fn mean_actions(&self) -> f64 {
+        match self.actions.len() {
+            0 => 0.0,
+            count => self.actions.iter().sum::<u64>() as f64 / count as f64,
+        }
+    }
+
+    pub fn actions_percentile(&self, percentile: f64) -> u64 {
+        nearest_rank(&self.actions, percentile).unwrap_or_default()
+    }
+}
+
+/// The smallest value with at least `percentile` percent of the values at
+/// or below it.
+fn nearest_rank<T: Copy + Ord>(values: &[T], percentile: f64) -> Option<T> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+impl fmt::Display for BatchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Batch: total={} solved={} unsolvable={} aborted={} invalid={}",
+            self.total(),
+            self.solved,
+            self.unsolvable,
+            self.aborted,
+            self.invalid
+        )?;
+        writeln!(
+            f,
+            "Time: mean={:.6}s p50={:.6}s p99={:.6}s",
+            self.mean_time().as_secs_f64(),
+            self.time_percentile(50.0).as_secs_f64(),
+            self.time_percentile(99.0).as_secs_f64()
+        )?;
+        write!(
+            f,
+            "Actions: mean={:.1} p50={} p99={}",
+            self.mean_actions(),
+            self.actions_percentile(50.0),
+            self.actions_percentile(99.0)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::algorithms::outcome::SolveStats;
+
+    fn outcome(status: SolveStatus, millis: u64, actions: u64) -> SolveOutcome {
+        SolveOutcome {
+            status,
+            grid: Vec::new(),
+            stats: SolveStats {
+                actions,
+                branches: 0,
+                elapsed: Duration::from_millis(millis),
+            },
+        }
+    }
+
+    #[test]
+    fn counts_every_status_and_keeps_invalid_puzzles_out_of_the_times() {
+        let mut stats = BatchStats::new();
+        stats.record(&outcome(SolveStatus::Solved, 10, 100));
+        stats.record(&outcome(SolveStatus::Solved, 30, 300));
+        stats.record(&outcome(SolveStatus::Unsolvable, 20, 200));
+        stats.record(&outcome(SolveStatus::Aborted, 40, 400));
+        stats.record_invalid();
+
+        assert_eq!(
+            (stats.solved, stats.unsolvable, stats.aborted, stats.invalid),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(stats.total(), 5);
+        assert_eq!(stats.mean_time(), Duration::from_millis(25));
+        assert_eq!(stats.mean_actions(), 250.0);
+        assert_eq!(stats.time_percentile(50.0), Duration::from_millis(20));
+        assert_eq!(stats.actions_percentile(95.0), 400);
+        assert_eq!(stats.actions_percentile(0.0), 100);
+    }
+
+    #[test]
+    fn an_empty_batch_reports_zeros() {
+        let stats = BatchStats::new();
+
+        assert_eq!(stats.mean_time(), Duration::ZERO);
+        assert_eq!(stats.time_percentile(99.0), Duration::ZERO);
+        assert_eq!(stats.mean_actions(), 0.0);
+        assert_eq!(stats.actions_percentile(50.0), 0);
+    }
+}
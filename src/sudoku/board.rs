@@ -1,12 +1,35 @@
+use std::fmt;
 use std::sync::mpsc::Sender;
 
-use crate::cli::game_updater::CliChannelEvent;
+use crate::sudoku::error::{Conflict, SudokuError};
 
-const BOARD_N: usize = 3;
+const VALUE_RADIX: u32 = 36;
 
-type Box = [[SudokuCell; BOARD_N]; BOARD_N];
-type Board = [[Box; BOARD_N]; BOARD_N];
+type Box = Vec<Vec<SudokuCell>>;
+type Board = Vec<Vec<Box>>;
 pub type CellType = u16;
+/// Plain values of a board by row then column, `0` standing for an empty cell.
+pub type Grid = Vec<Vec<CellType>>;
+/// One bit per value, bit `value - 1` set when `value` is present.
+pub type CandidateMask = u64;
+
+/// A row, column or box, numbered from 0 like the coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Row(index) => write!(f, "row {}", index + 1),
+            Unit::Column(index) => write!(f, "column {}", index + 1),
+            Unit::Box(index) => write!(f, "box {}", index + 1),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct SudokuCell {
@@ -20,91 +43,324 @@ impl SudokuCell {
     fn new(value: Option<CellType>) -> Self {
         SudokuCell {
             value,
-            editable: if value.is_some() { false } else { true },
+            editable: value.is_none(),
             x: 0,
             y: 0,
         }
     }
 }
-#[derive(Debug)]
+
+/// What a board reports to whoever watches it, see `SudokuBoard::with_events`.
+/// Every `update_value` with a value sends `Try`, then `Accept` when it is
+/// placed or `Reject` when it doesn't fit; clearing a value sends `Backtrack`.
+#[derive(Debug, Clone, Copy)]
+pub enum BoardEvent {
+    /// A cell as it is, sent for every cell when watching starts and when
+    /// the board is finished.
+    Update(SudokuCell),
+    /// `value` is about to be checked against the cell's row, column and box.
+    Try {
+        x: usize,
+        y: usize,
+        value: CellType,
+    },
+    Accept(SudokuCell),
+    /// `value` clashes with the one at `conflict_with`, see
+    /// `SudokuError::ConflictingValue`.
+    Reject {
+        x: usize,
+        y: usize,
+        value: CellType,
+        conflict_with: (usize, usize),
+    },
+    Backtrack(SudokuCell),
+    /// The pencil marks of an empty cell, see
+    /// `SudokuBoard::with_candidate_events`.
+    Candidates {
+        x: usize,
+        y: usize,
+        candidates: CandidateMask,
+    },
+    /// Not sent by the board, the caller sends it once the solver is done.
+    Finished,
+}
+
+#[derive(Debug, Clone)]
 pub struct SudokuBoard {
     board: Board,
-    board_tx: Sender<CliChannelEvent>,
+    box_width: usize,
+    box_height: usize,
+    rows: Vec<CandidateMask>,
+    columns: Vec<CandidateMask>,
+    boxes: Vec<CandidateMask>,
+    board_tx: Option<Sender<BoardEvent>>,
+    candidate_events: bool,
 }
 
 impl SudokuBoard {
-    pub const BOARD_N: usize = BOARD_N;
-    pub const BOARD_MAX_NUMBER: usize = Self::BOARD_N.pow(2);
+    /// Parses a single cell character. Digits `1`..`9` are followed by the
+    /// letters `A`.. so boards bigger than 9x9 can be written one character
+    /// per cell (`A` = 10, `G` = 16, `P` = 25).
+    pub fn value_from_char(value: char) -> Option<CellType> {
+        value.to_digit(VALUE_RADIX).map(|digit| digit as CellType)
+    }
 
-    pub fn valid_values() -> Vec<CellType> {
-        (1..=SudokuBoard::BOARD_MAX_NUMBER as CellType).collect()
+    pub fn value_to_char(value: CellType) -> char {
+        char::from_digit(value as u32, VALUE_RADIX)
+            .map(|digit| digit.to_ascii_uppercase())
+            .unwrap_or('?')
     }
 
-    fn initialize_box() -> Box {
-        ([[SudokuCell::new(None); Self::BOARD_N]; Self::BOARD_N]).into()
+    pub fn value_mask(value: CellType) -> CandidateMask {
+        1 << (value - 1)
     }
 
-    fn initialize_board() -> Board {
-        (
-            [
-                Self::initialize_box(),
-                Self::initialize_box(),
-                Self::initialize_box(),
-            ],
-            [
-                Self::initialize_box(),
-                Self::initialize_box(),
-                Self::initialize_box(),
-            ],
-            [
-                Self::initialize_box(),
-                Self::initialize_box(),
-                Self::initialize_box(),
-            ],
-        )
-            .into()
+    pub fn mask_values(mask: CandidateMask) -> impl Iterator<Item = CellType> {
+        (0..CandidateMask::BITS)
+            .filter(move |bit| mask & (1 << bit) != 0)
+            .map(|bit| bit as CellType + 1)
+    }
+
+    pub fn box_width(&self) -> usize {
+        self.box_width
+    }
+
+    pub fn box_height(&self) -> usize {
+        self.box_height
+    }
+
+    pub fn board_max_number(&self) -> usize {
+        self.box_width * self.box_height
+    }
+
+    pub fn full_mask(&self) -> CandidateMask {
+        CandidateMask::MAX >> (CandidateMask::BITS as usize - self.board_max_number())
+    }
+
+    pub fn box_index(&self, x: usize, y: usize) -> usize {
+        (x / self.box_height) * self.box_height + y / self.box_width
+    }
+
+    /// Values that no other cell in the row, column or box already holds.
+    /// The cell's own value is ignored so a filled cell reports the values
+    /// it could be changed to.
+    pub fn candidates(&self, x: usize, y: usize) -> CandidateMask {
+        let own_value = self
+            .find_cell_from_coordinates(x, y)
+            .ok()
+            .and_then(|cell| cell.value)
+            .map_or(0, Self::value_mask);
+        let used = self.rows[x] | self.columns[y] | self.boxes[self.box_index(x, y)];
+
+        self.full_mask() & !(used & !own_value)
+    }
+
+    fn initialize_box(box_width: usize, box_height: usize) -> Box {
+        vec![vec![SudokuCell::new(None); box_width]; box_height]
+    }
+
+    fn initialize_board(box_width: usize, box_height: usize) -> Board {
+        // A row of boxes holds `box_height` boxes side by side and there are
+        // `box_width` of those rows stacked vertically.
+        vec![vec![Self::initialize_box(box_width, box_height); box_height]; box_width]
+    }
+
+    /// Picks the most square box shape for a board with `lines` rows: boxes
+    /// are never taller than they are wide, so 6 lines gives 2x3 boxes (two
+    /// rows of three columns) and 12 lines gives 3x4 boxes. Boxes one row
+    /// high aren't guessed, so a prime number of lines has no shape and
+    /// needs an explicit box size.
+    pub fn box_size_from_lines(lines: usize) -> Option<(usize, usize)> {
+        (2..=lines)
+            .take_while(|height| height * height <= lines)
+            .filter(|height| lines.is_multiple_of(*height))
+            .last()
+            .map(|box_height| (lines / box_height, box_height))
+    }
+
+    pub fn new(list: Vec<Vec<Option<CellType>>>) -> Result<Self, SudokuError> {
+        match Self::box_size_from_lines(list.len()) {
+            Some((box_width, box_height)) => Self::with_box_size(list, box_width, box_height),
+            None => Err(SudokuError::WrongRowCount {
+                expected: None,
+                found: list.len(),
+            }),
+        }
     }
 
-    pub fn new(
+    pub fn with_box_size(
         list: Vec<Vec<Option<CellType>>>,
-        board_tx: Sender<CliChannelEvent>,
-    ) -> Result<Self, String> {
-        if list.len() != Self::BOARD_MAX_NUMBER {
-            return Err("The provided list must have 9 lines".to_string());
+        box_width: usize,
+        box_height: usize,
+    ) -> Result<Self, SudokuError> {
+        let board_max_number = box_width * box_height;
+
+        if board_max_number == 0 || board_max_number > CandidateMask::BITS as usize {
+            return Err(SudokuError::InvalidBoxSize {
+                box_width,
+                box_height,
+            });
+        }
+
+        if list.len() != board_max_number {
+            return Err(SudokuError::WrongRowCount {
+                expected: Some(board_max_number),
+                found: list.len(),
+            });
         }
 
         let mut sudoku_board: SudokuBoard = SudokuBoard {
-            board: Self::initialize_board(),
-            board_tx,
+            board: Self::initialize_board(box_width, box_height),
+            box_width,
+            box_height,
+            rows: vec![0; board_max_number],
+            columns: vec![0; board_max_number],
+            boxes: vec![0; board_max_number],
+            board_tx: None,
+            candidate_events: false,
         };
 
         for (line_index, row) in list.iter().enumerate() {
-            if row.len() != Self::BOARD_MAX_NUMBER.into() {
-                return Err("The provided list must have 9 lines".to_string());
+            if row.len() != board_max_number {
+                return Err(SudokuError::WrongRowLength {
+                    row: line_index,
+                    expected: board_max_number,
+                    found: row.len(),
+                });
             }
 
             for (column_index, value) in row.iter().enumerate() {
-                let cell_result =
-                    sudoku_board.find_cell_from_coordinates_mut(line_index, column_index);
-                if let Ok(cell) = cell_result {
-                    cell.value = *value;
-                    cell.editable = value.is_none();
-                    cell.x = line_index;
-                    cell.y = column_index;
-                } else {
-                    return Err(cell_result.unwrap_err());
+                if let Some(value) = value
+                    && !sudoku_board.is_valid_value(*value)
+                {
+                    return Err(SudokuError::ValueOutOfRange {
+                        x: line_index,
+                        y: column_index,
+                        value: *value,
+                        max: board_max_number,
+                    });
+                }
+
+                let cell = sudoku_board.find_cell_from_coordinates_mut(line_index, column_index)?;
+                cell.value = *value;
+                cell.editable = value.is_none();
+                cell.x = line_index;
+                cell.y = column_index;
+
+                if let Some(value) = value {
+                    sudoku_board.set_mask(line_index, column_index, *value, true);
                 }
             }
         }
 
+        let conflicts = sudoku_board.find_given_conflicts();
+        if !conflicts.is_empty() {
+            return Err(SudokuError::ConflictingGivens(conflicts));
+        }
+
         Ok(sudoku_board)
     }
 
-    fn decompose_coordinates(x: usize, y: usize) -> (usize, usize, usize, usize) {
-        let board_row_index = x / Self::BOARD_N;
-        let board_column_index = y / Self::BOARD_N;
-        let box_row_index = x % Self::BOARD_N;
-        let box_column_index = y % Self::BOARD_N;
+    /// The values by row then column, `0` for empty cells.
+    pub fn grid(&self) -> Grid {
+        (0..self.board_max_number())
+            .map(|x| {
+                (0..self.board_max_number())
+                    .map(|y| {
+                        self.find_cell_from_coordinates(x, y)
+                            .ok()
+                            .and_then(|cell| cell.value)
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Cells of a unit, in reading order.
+    pub fn unit_cells(&self, unit: Unit) -> Vec<(usize, usize)> {
+        let board_max_number = self.board_max_number();
+
+        match unit {
+            Unit::Row(x) => (0..board_max_number).map(|y| (x, y)).collect(),
+            Unit::Column(y) => (0..board_max_number).map(|x| (x, y)).collect(),
+            Unit::Box(index) => {
+                let top = (index / self.box_height) * self.box_height;
+                let left = (index % self.box_height) * self.box_width;
+
+                (0..board_max_number)
+                    .map(|offset| {
+                        (
+                            top + offset / self.box_width,
+                            left + offset % self.box_width,
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn find_given_conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        let units = (0..self.board_max_number())
+            .map(Unit::Row)
+            .chain((0..self.board_max_number()).map(Unit::Column))
+            .chain((0..self.board_max_number()).map(Unit::Box));
+
+        for unit in units {
+            let givens: Vec<((usize, usize), CellType)> = self
+                .unit_cells(unit)
+                .into_iter()
+                .filter_map(|(x, y)| {
+                    let value = self.find_cell_from_coordinates(x, y).ok()?.value?;
+                    Some(((x, y), value))
+                })
+                .collect();
+
+            for (position, &(first, value)) in givens.iter().enumerate() {
+                for &(second, other_value) in &givens[position + 1..] {
+                    if value == other_value {
+                        conflicts.push(Conflict {
+                            value,
+                            first,
+                            second,
+                            unit,
+                        });
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Sends every cell to `board_tx` now and again when the board is
+    /// finished, and each change in between, for a display to follow along.
+    /// Boards without it stay silent.
+    pub fn with_events(mut self, board_tx: Sender<BoardEvent>) -> Self {
+        self.board_tx = Some(board_tx);
+        self.send_cells();
+        self
+    }
+
+    /// Also sends the candidates of every empty cell along with the cells,
+    /// and those of the empty cells sharing a unit with each changed one.
+    /// Call it before `with_events`.
+    pub fn with_candidate_events(mut self) -> Self {
+        self.candidate_events = true;
+        self
+    }
+
+    pub fn without_events(mut self) -> Self {
+        self.board_tx = None;
+        self
+    }
+
+    fn decompose_coordinates(&self, x: usize, y: usize) -> (usize, usize, usize, usize) {
+        let board_row_index = x / self.box_height;
+        let board_column_index = y / self.box_width;
+        let box_row_index = x % self.box_height;
+        let box_column_index = y % self.box_width;
         (
             board_row_index,
             board_column_index,
@@ -113,16 +369,23 @@ impl SudokuBoard {
         )
     }
 
-    pub fn find_cell_from_coordinates(&self, x: usize, y: usize) -> Result<&SudokuCell, String> {
-        let decomposed_coordinates = Self::decompose_coordinates(x, y);
-        let sudoku_box: &Box = &self.board[decomposed_coordinates.0][decomposed_coordinates.1];
-        let cell_result: Option<&SudokuCell> =
-            Some(&sudoku_box[decomposed_coordinates.2][decomposed_coordinates.3]);
+    pub fn find_cell_from_coordinates(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> Result<&SudokuCell, SudokuError> {
+        let decomposed_coordinates = self.decompose_coordinates(x, y);
+        let cell_result: Option<&SudokuCell> = self
+            .board
+            .get(decomposed_coordinates.0)
+            .and_then(|boxes| boxes.get(decomposed_coordinates.1))
+            .and_then(|sudoku_box| sudoku_box.get(decomposed_coordinates.2))
+            .and_then(|row| row.get(decomposed_coordinates.3));
 
         if let Some(cell) = cell_result {
             Ok(cell)
         } else {
-            Err(format!("Cell not found at coordinates ({}, {})", x, y))
+            Err(SudokuError::InvalidCoordinates { x, y })
         }
     }
 
@@ -130,17 +393,19 @@ impl SudokuBoard {
         &mut self,
         x: usize,
         y: usize,
-    ) -> Result<&mut SudokuCell, String> {
-        let decomposed_coordinates = Self::decompose_coordinates(x, y);
-        let sudoku_box: &mut Box =
-            &mut self.board[decomposed_coordinates.0][decomposed_coordinates.1];
-        let cell_result: Option<&mut SudokuCell> =
-            Some(&mut sudoku_box[decomposed_coordinates.2][decomposed_coordinates.3]);
+    ) -> Result<&mut SudokuCell, SudokuError> {
+        let decomposed_coordinates = self.decompose_coordinates(x, y);
+        let cell_result: Option<&mut SudokuCell> = self
+            .board
+            .get_mut(decomposed_coordinates.0)
+            .and_then(|boxes| boxes.get_mut(decomposed_coordinates.1))
+            .and_then(|sudoku_box| sudoku_box.get_mut(decomposed_coordinates.2))
+            .and_then(|row| row.get_mut(decomposed_coordinates.3));
 
         if let Some(cell) = cell_result {
             Ok(cell)
         } else {
-            Err(format!("Cell not found at coordinates ({}, {})", x, y))
+            Err(SudokuError::InvalidCoordinates { x, y })
         }
     }
 
@@ -149,156 +414,244 @@ impl SudokuBoard {
         x: usize,
         y: usize,
         value: Option<CellType>,
-    ) -> Result<(), String> {
-        if x >= Self::BOARD_MAX_NUMBER.into() || y >= Self::BOARD_MAX_NUMBER.into() {
-            return Err(format!("Invalid coordinates ({}, {})", x, y));
+    ) -> Result<(), SudokuError> {
+        if x >= self.board_max_number() || y >= self.board_max_number() {
+            return Err(SudokuError::InvalidCoordinates { x, y });
         }
 
-        if !self.is_valid_insertion(x, y, value) {
-            return Err("Invalid Insertion".to_string());
+        if let Some(value) = value
+            && !self.is_valid_value(value)
+        {
+            return Err(SudokuError::ValueOutOfRange {
+                x,
+                y,
+                value,
+                max: self.board_max_number(),
+            });
         }
 
-        let result = {
-            match self.find_cell_from_coordinates_mut(x, y) {
-                Ok(cell_ptr) => {
-                    cell_ptr.value = value;
+        if let Some(value) = value {
+            self.send(BoardEvent::Try { x, y, value });
+        }
 
-                    Ok(())
-                }
-                Err(e) => Err(e),
-            }
-        };
+        if !self.is_valid_insertion(x, y, value) {
+            let value = value.unwrap_or_default();
+            let conflict_with = self.find_conflict(x, y, value);
+            self.send(BoardEvent::Reject {
+                x,
+                y,
+                value,
+                conflict_with,
+            });
+
+            return Err(SudokuError::ConflictingValue {
+                x,
+                y,
+                value,
+                conflict_with,
+            });
+        }
 
-        result
-    }
+        let cell_ptr = self.find_cell_from_coordinates_mut(x, y)?;
+        let previous_value = std::mem::replace(&mut cell_ptr.value, value);
+        let cell = *cell_ptr;
 
-    fn calculate_final_cost(&self) -> Result<u16, String> {
-        let row_and_column_cost = {
-            let mut cost = 0;
-            for x in 0..Self::BOARD_MAX_NUMBER {
-                let mut missing_values_in_row = Self::valid_values();
-                let mut missing_values_in_column = Self::valid_values();
+        match value {
+            Some(_) => self.send(BoardEvent::Accept(cell)),
+            None => self.send(BoardEvent::Backtrack(cell)),
+        }
 
-                for y in 0..Self::BOARD_MAX_NUMBER {
-                    let cell_row_result = self.find_cell_from_coordinates(x, y)?;
-                    let cell_column_result = self.find_cell_from_coordinates(y, x)?;
+        if let Some(previous_value) = previous_value {
+            self.set_mask(x, y, previous_value, false);
+        }
 
-                    if cell_row_result.value.is_none() {
-                        return Err(format!("Cell {},{} is empty", x, y));
-                    } else if cell_column_result.value.is_none() {
-                        return Err(format!("Cell {},{} is empty", y, x));
-                    }
+        if let Some(value) = value {
+            self.set_mask(x, y, value, true);
+        }
 
-                    missing_values_in_row.retain(|&value| value != cell_row_result.value.unwrap());
-                    missing_values_in_column
-                        .retain(|&value| value != cell_column_result.value.unwrap());
-                }
+        if self.candidate_events && self.board_tx.is_some() {
+            let box_index = self.box_index(x, y);
 
-                cost += missing_values_in_row.iter().sum::<CellType>()
-                    + missing_values_in_column.iter().sum::<CellType>();
+            for (peer_x, peer_y) in self
+                .unit_cells(Unit::Row(x))
+                .into_iter()
+                .chain(self.unit_cells(Unit::Column(y)))
+                .chain(self.unit_cells(Unit::Box(box_index)))
+            {
+                self.send_candidates(peer_x, peer_y);
             }
+        }
 
-            cost
-        };
+        Ok(())
+    }
+
+    /// Like `update_value` for solvers, which only need to know whether the
+    /// value fits: the conflicting cell is only looked up for a watcher.
+    pub fn try_update_value(&mut self, x: usize, y: usize, value: Option<CellType>) -> bool {
+        if self.is_valid_insertion(x, y, value) {
+            return self.update_value(x, y, value).is_ok();
+        }
+
+        if self.board_tx.is_some() {
+            let value = value.unwrap_or_default();
+            self.send(BoardEvent::Try { x, y, value });
+            self.send(BoardEvent::Reject {
+                x,
+                y,
+                value,
+                conflict_with: self.find_conflict(x, y, value),
+            });
+        }
 
-        let box_cost = {
-            let mut cost = 0;
+        false
+    }
 
-            for boxes in self.board {
-                let mut missing_values_in_box = Self::valid_values();
+    /// The cell holding `value` that the row, column or box check trips on,
+    /// the cell itself when it already holds it. Only the three units of
+    /// `(x, y)` are scanned.
+    fn find_conflict(&self, x: usize, y: usize, value: CellType) -> (usize, usize) {
+        let board_max_number = self.board_max_number();
+        let top = x - x % self.box_height;
+        let left = y - y % self.box_width;
+
+        (0..board_max_number)
+            .map(|other_y| (x, other_y))
+            .chain((0..board_max_number).map(|other_x| (other_x, y)))
+            .chain((0..board_max_number).map(|offset| {
+                (
+                    top + offset / self.box_width,
+                    left + offset % self.box_width,
+                )
+            }))
+            .filter(|&cell| cell != (x, y))
+            .find(|&(other_x, other_y)| {
+                self.find_cell_from_coordinates(other_x, other_y)
+                    .is_ok_and(|cell| cell.value == Some(value))
+            })
+            .unwrap_or((x, y))
+    }
 
-                for sudoku_box in boxes {
-                    for row in sudoku_box {
-                        for cell in row {
-                            if cell.value.is_none() {
-                                return Err(format!("Cell {}, {} is empty", cell.x, cell.y));
-                            }
+    fn set_mask(&mut self, x: usize, y: usize, value: CellType, present: bool) {
+        let bit = Self::value_mask(value);
+        let box_index = self.box_index(x, y);
+
+        for mask in [
+            &mut self.rows[x],
+            &mut self.columns[y],
+            &mut self.boxes[box_index],
+        ] {
+            if present {
+                *mask |= bit;
+            } else {
+                *mask &= !bit;
+            }
+        }
+    }
 
-                            missing_values_in_box.retain(|&value| cell.value.unwrap() != value);
-                        }
-                    }
+    fn calculate_final_cost(&self) -> Result<u64, SudokuError> {
+        for x in 0..self.board_max_number() {
+            for y in 0..self.board_max_number() {
+                if self.find_cell_from_coordinates(x, y)?.value.is_none() {
+                    return Err(SudokuError::Incomplete { x, y });
                 }
-
-                cost += missing_values_in_box.iter().sum::<CellType>();
             }
+        }
 
-            cost
+        let missing_cost = |mask: &CandidateMask| -> u64 {
+            Self::mask_values(self.full_mask() & !mask)
+                .map(u64::from)
+                .sum()
         };
 
-        let total = row_and_column_cost
-            .checked_add(box_cost)
-            .ok_or_else(|| "Cost overflow".to_string())?;
-
-        Ok(total)
+        Ok(self
+            .rows
+            .iter()
+            .chain(self.columns.iter())
+            .chain(self.boxes.iter())
+            .map(missing_cost)
+            .sum())
     }
 
-    pub fn finish(&self) -> Result<(), String> {
+    pub fn finish(&self) -> Result<(), SudokuError> {
         let cost = self.calculate_final_cost()?;
 
         if cost > 0 {
-            return Err("Sudoku does not have a optimal solution".to_string());
+            return Err(SudokuError::NoSolution);
         }
 
-        for x in 0..Self::BOARD_MAX_NUMBER {
-            for y in 0..Self::BOARD_MAX_NUMBER {
-                let cell_result = self.find_cell_from_coordinates(x, y);
+        self.send_cells();
 
-                if let Ok(cell) = cell_result {
-                    let _ = self.board_tx.send(CliChannelEvent::Update(*cell));
+        Ok(())
+    }
+
+    fn send(&self, event: BoardEvent) {
+        if let Some(board_tx) = &self.board_tx {
+            let _ = board_tx.send(event);
+        }
+    }
+
+    fn send_candidates(&self, x: usize, y: usize) {
+        if self
+            .find_cell_from_coordinates(x, y)
+            .is_ok_and(|cell| cell.value.is_none())
+        {
+            self.send(BoardEvent::Candidates {
+                x,
+                y,
+                candidates: self.candidates(x, y),
+            });
+        }
+    }
+
+    fn send_cells(&self) {
+        if self.board_tx.is_none() {
+            return;
+        }
+
+        for x in 0..self.board_max_number() {
+            for y in 0..self.board_max_number() {
+                if let Ok(cell) = self.find_cell_from_coordinates(x, y) {
+                    self.send(BoardEvent::Update(*cell));
+                }
+
+                if self.candidate_events {
+                    self.send_candidates(x, y);
                 }
             }
         }
-
-        Ok(())
     }
 
     pub fn is_valid_insertion(&self, x: usize, y: usize, new_value: Option<CellType>) -> bool {
         if let Some(value) = new_value {
-            return self.is_valid_box(x, y, value)
+            self.is_valid_value(value)
+                && self.is_valid_box(x, y, value)
                 && self.is_valid_line(x, value)
-                && self.is_valid_column(y, value);
+                && self.is_valid_column(y, value)
         } else {
-            return true;
+            true
         }
     }
 
+    fn is_valid_value(&self, value: CellType) -> bool {
+        value >= 1 && value as usize <= self.board_max_number()
+    }
+
     fn is_valid_box(&self, x: usize, y: usize, new_value: CellType) -> bool {
-        let decomposed_coordinates = Self::decompose_coordinates(x, y);
-        let sudoku_box = self.board[decomposed_coordinates.0][decomposed_coordinates.1];
-        sudoku_box.iter().all(|&lines| {
-            let result = lines.iter().all(|&cell| cell.value != Some(new_value));
-            return result;
-        })
+        self.boxes[self.box_index(x, y)] & Self::value_mask(new_value) == 0
     }
 
     fn is_valid_line(&self, x: usize, new_value: CellType) -> bool {
-        for y in 0..Self::BOARD_MAX_NUMBER.into() {
-            let cell = self.find_cell_from_coordinates(x, y).unwrap();
-
-            if cell.value == Some(new_value) {
-                return false;
-            }
-        }
-
-        return true;
+        self.rows[x] & Self::value_mask(new_value) == 0
     }
 
     fn is_valid_column(&self, y: usize, new_value: CellType) -> bool {
-        for x in 0..Self::BOARD_MAX_NUMBER.into() {
-            let cell = self.find_cell_from_coordinates(x, y).unwrap();
-
-            if cell.value == Some(new_value) {
-                return false;
-            }
-        }
-
-        return true;
+        self.columns[y] & Self::value_mask(new_value) == 0
     }
 
     pub fn get_editable_cells(&self) -> Vec<(usize, usize)> {
         let mut editable_cells = vec![];
-        for x in 0..Self::BOARD_MAX_NUMBER.into() {
-            for y in 0..Self::BOARD_MAX_NUMBER.into() {
+        for x in 0..self.board_max_number() {
+            for y in 0..self.board_max_number() {
                 let cell = self.find_cell_from_coordinates(x, y).unwrap();
 
                 if cell.editable {
@@ -310,3 +663,247 @@ impl SudokuBoard {
         editable_cells
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn empty_board(lines: usize) -> SudokuBoard {
+        SudokuBoard::new(vec![vec![None; lines]; lines]).unwrap()
+    }
+
+    fn candidate_events(events: &mpsc::Receiver<BoardEvent>) -> Vec<(usize, usize, CandidateMask)> {
+        events
+            .try_iter()
+            .filter_map(|event| match event {
+                BoardEvent::Candidates { x, y, candidates } => Some((x, y, candidates)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn box_shape_is_the_most_square_one() {
+        assert_eq!(SudokuBoard::box_size_from_lines(4), Some((2, 2)));
+        assert_eq!(SudokuBoard::box_size_from_lines(6), Some((3, 2)));
+        assert_eq!(SudokuBoard::box_size_from_lines(9), Some((3, 3)));
+        assert_eq!(SudokuBoard::box_size_from_lines(12), Some((4, 3)));
+    }
+
+    #[test]
+    fn prime_line_counts_have_no_box_shape() {
+        for lines in [0, 1, 2, 5, 7, 11] {
+            assert_eq!(SudokuBoard::box_size_from_lines(lines), None);
+        }
+
+        assert!(matches!(
+            SudokuBoard::new(vec![vec![None; 7]; 7]),
+            Err(SudokuError::WrongRowCount {
+                expected: None,
+                found: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn masks_hold_one_bit_per_value() {
+        assert_eq!(SudokuBoard::value_mask(1), 0b1);
+        assert_eq!(SudokuBoard::value_mask(64), 1 << 63);
+        assert_eq!(
+            SudokuBoard::mask_values(0b1010_0001).collect::<Vec<_>>(),
+            [1, 6, 8]
+        );
+
+        let board = SudokuBoard::new(vec![
+            vec![Some(1), None, None, None],
+            vec![None, None, Some(2), None],
+            vec![None, None, None, None],
+            vec![None, Some(3), None, None],
+        ])
+        .unwrap();
+
+        // Row 0 holds 1, column 1 holds 3 and the top left box holds 1.
+        assert_eq!(board.candidates(0, 1), 0b1010);
+        // A filled cell's own value doesn't count against it.
+        assert_eq!(board.candidates(0, 0), 0b1111);
+    }
+
+    #[test]
+    fn repeated_givens_are_listed_once_per_unit() {
+        let error = SudokuBoard::new(vec![
+            vec![Some(1), Some(1), None, None],
+            vec![None; 4],
+            vec![None; 4],
+            vec![None; 4],
+        ])
+        .unwrap_err();
+
+        let SudokuError::ConflictingGivens(conflicts) = error else {
+            panic!("expected conflicting givens, got {error:?}");
+        };
+        assert_eq!(
+            conflicts
+                .iter()
+                .map(|conflict| (
+                    conflict.value,
+                    conflict.first,
+                    conflict.second,
+                    conflict.unit
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (1, (0, 0), (0, 1), Unit::Row(0)),
+                (1, (0, 0), (0, 1), Unit::Box(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_boards_are_refused() {
+        assert!(matches!(
+            SudokuBoard::with_box_size(vec![vec![None; 4]; 4], 0, 4),
+            Err(SudokuError::InvalidBoxSize {
+                box_width: 0,
+                box_height: 4
+            })
+        ));
+        assert!(matches!(
+            SudokuBoard::with_box_size(vec![vec![None; 4]; 4], 3, 2),
+            Err(SudokuError::WrongRowCount {
+                expected: Some(6),
+                found: 4
+            })
+        ));
+        assert!(matches!(
+            SudokuBoard::new(vec![
+                vec![None; 4],
+                vec![None; 3],
+                vec![None; 4],
+                vec![None; 4]
+            ]),
+            Err(SudokuError::WrongRowLength {
+                row: 1,
+                expected: 4,
+                found: 3
+            })
+        ));
+
+        let mut rows = vec![vec![None; 4]; 4];
+        rows[2][3] = Some(5);
+        assert!(matches!(
+            SudokuBoard::new(rows),
+            Err(SudokuError::ValueOutOfRange {
+                x: 2,
+                y: 3,
+                value: 5,
+                max: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn updates_that_break_the_rules_are_refused() {
+        let mut board = empty_board(4);
+        board.update_value(1, 1, Some(3)).unwrap();
+
+        assert!(matches!(
+            board.update_value(0, 0, Some(3)),
+            Err(SudokuError::ConflictingValue {
+                x: 0,
+                y: 0,
+                value: 3,
+                conflict_with: (1, 1)
+            })
+        ));
+        assert!(matches!(
+            board.update_value(4, 0, Some(1)),
+            Err(SudokuError::InvalidCoordinates { x: 4, y: 0 })
+        ));
+        assert!(matches!(
+            board.update_value(0, 0, Some(0)),
+            Err(SudokuError::ValueOutOfRange { value: 0, .. })
+        ));
+        assert!(matches!(
+            board.finish(),
+            Err(SudokuError::Incomplete { x: 0, y: 0 })
+        ));
+    }
+
+    #[test]
+    fn each_update_sends_a_try_then_its_outcome() {
+        let (board_tx, board_rx) = mpsc::channel();
+        let mut board = empty_board(4).with_events(board_tx);
+        assert_eq!(board_rx.try_iter().count(), 16);
+
+        board.update_value(0, 0, Some(1)).unwrap();
+        assert!(matches!(
+            board_rx.try_iter().collect::<Vec<_>>()[..],
+            [
+                BoardEvent::Try {
+                    x: 0,
+                    y: 0,
+                    value: 1
+                },
+                BoardEvent::Accept(SudokuCell {
+                    x: 0,
+                    y: 0,
+                    value: Some(1),
+                    ..
+                })
+            ]
+        ));
+
+        assert!(!board.try_update_value(0, 3, Some(1)));
+        assert!(matches!(
+            board_rx.try_iter().collect::<Vec<_>>()[..],
+            [
+                BoardEvent::Try {
+                    x: 0,
+                    y: 3,
+                    value: 1
+                },
+                BoardEvent::Reject {
+                    x: 0,
+                    y: 3,
+                    value: 1,
+                    conflict_with: (0, 0),
+                }
+            ]
+        ));
+
+        board.update_value(0, 0, None).unwrap();
+        assert!(matches!(
+            board_rx.try_iter().collect::<Vec<_>>()[..],
+            [BoardEvent::Backtrack(SudokuCell {
+                x: 0,
+                y: 0,
+                value: None,
+                ..
+            })]
+        ));
+    }
+
+    #[test]
+    fn peers_get_the_candidates_left_after_a_placement() {
+        let (board_tx, board_rx) = mpsc::channel();
+        let mut board = empty_board(4).with_candidate_events().with_events(board_tx);
+        board_rx.try_iter().for_each(drop);
+
+        board.update_value(0, 0, Some(1)).unwrap();
+        let placed = candidate_events(&board_rx);
+
+        // Row, column and box of (0, 0), the cell itself is filled.
+        assert!(placed.iter().all(|&(x, y, _)| (x, y) != (0, 0)));
+        assert!(placed.contains(&(0, 3, 0b1110)));
+        assert!(placed.contains(&(3, 0, 0b1110)));
+        assert!(placed.contains(&(1, 1, 0b1110)));
+
+        board.update_value(0, 0, None).unwrap();
+        let cleared = candidate_events(&board_rx);
+
+        assert!(cleared.contains(&(0, 0, 0b1111)));
+        assert!(cleared.contains(&(0, 3, 0b1111)));
+    }
+}
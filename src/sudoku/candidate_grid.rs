@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use crate::sudoku::board::{CandidateMask, CellType, Grid, SudokuBoard, Unit};
+
+/// Row, column and box membership of every cell, shared between all the
+/// copies of a grid made while searching.
+#[derive(Debug)]
+struct Layout {
+    board_max_number: usize,
+    units: Vec<Vec<usize>>,
+    peers: Vec<Vec<usize>>,
+    boxes: Vec<usize>,
+}
+
+impl Layout {
+    fn new(board: &SudokuBoard) -> Self {
+        let board_max_number = board.board_max_number();
+        let cells = board_max_number * board_max_number;
+        let mut units = vec![Vec::with_capacity(board_max_number); board_max_number * 3];
+        let mut boxes = Vec::with_capacity(cells);
+
+        for x in 0..board_max_number {
+            for y in 0..board_max_number {
+                let index = x * board_max_number + y;
+                units[x].push(index);
+                units[board_max_number + y].push(index);
+                units[board_max_number * 2 + board.box_index(x, y)].push(index);
+                boxes.push(board.box_index(x, y));
+            }
+        }
+
+        let mut peers = vec![Vec::new(); cells];
+        for unit in &units {
+            for &cell in unit {
+                for &peer in unit {
+                    if peer != cell && !peers[cell].contains(&peer) {
+                        peers[cell].push(peer);
+                    }
+                }
+            }
+        }
+
+        Layout {
+            board_max_number,
+            units,
+            peers,
+            boxes,
+        }
+    }
+}
+
+/// Values and pencil-mark candidates of every cell, indexed by
+/// `x * board_max_number + y`. Cheap to clone, which makes it handy for
+/// solvers that branch.
+#[derive(Debug, Clone)]
+pub struct CandidateGrid {
+    layout: Arc<Layout>,
+    values: Vec<Option<CellType>>,
+    candidates: Vec<CandidateMask>,
+}
+
+impl CandidateGrid {
+    pub fn from_board(board: &SudokuBoard) -> Self {
+        let layout = Layout::new(board);
+        let board_max_number = layout.board_max_number;
+        let mut values = Vec::with_capacity(board_max_number * board_max_number);
+        let mut candidates = Vec::with_capacity(board_max_number * board_max_number);
+
+        for x in 0..board_max_number {
+            for y in 0..board_max_number {
+                let value = board
+                    .find_cell_from_coordinates(x, y)
+                    .ok()
+                    .and_then(|cell| cell.value);
+
+                values.push(value);
+                candidates.push(match value {
+                    Some(value) => SudokuBoard::value_mask(value),
+                    None => board.candidates(x, y),
+                });
+            }
+        }
+
+        CandidateGrid {
+            layout: Arc::new(layout),
+            values,
+            candidates,
+        }
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn full_mask(&self) -> CandidateMask {
+        CandidateMask::MAX >> (CandidateMask::BITS as usize - self.layout.board_max_number)
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        x * self.layout.board_max_number + y
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.values
+            .chunks(self.layout.board_max_number)
+            .map(|row| row.iter().map(|value| value.unwrap_or_default()).collect())
+            .collect()
+    }
+
+    pub fn board_max_number(&self) -> usize {
+        self.layout.board_max_number
+    }
+
+    pub fn coordinates(&self, index: usize) -> (usize, usize) {
+        (
+            index / self.layout.board_max_number,
+            index % self.layout.board_max_number,
+        )
+    }
+
+    pub fn box_of(&self, index: usize) -> usize {
+        self.layout.boxes[index]
+    }
+
+    /// Every unit of the board: the rows first, then the columns, then the
+    /// boxes, each listing its cell indexes.
+    pub fn units(&self) -> &[Vec<usize>] {
+        &self.layout.units
+    }
+
+    pub fn unit(&self, unit_index: usize) -> Unit {
+        let board_max_number = self.layout.board_max_number;
+
+        match unit_index / board_max_number {
+            0 => Unit::Row(unit_index),
+            1 => Unit::Column(unit_index - board_max_number),
+            _ => Unit::Box(unit_index - board_max_number * 2),
+        }
+    }
+
+    pub fn peers(&self, index: usize) -> &[usize] {
+        &self.layout.peers[index]
+    }
+
+    pub fn sees(&self, first: usize, second: usize) -> bool {
+        first != second && self.layout.peers[first].contains(&second)
+    }
+
+    pub fn value(&self, index: usize) -> Option<CellType> {
+        self.values[index]
+    }
+
+    pub fn candidates(&self, index: usize) -> CandidateMask {
+        self.candidates[index]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.values.iter().all(Option::is_some)
+    }
+
+    /// Removes a candidate, returning whether it was there.
+    pub fn eliminate(&mut self, index: usize, value: CellType) -> bool {
+        let bit = SudokuBoard::value_mask(value);
+        let present = self.candidates[index] & bit != 0;
+
+        self.candidates[index] &= !bit;
+        present
+    }
+
+    /// Fills a cell and removes the value from the candidates of its peers.
+    pub fn place(&mut self, index: usize, value: CellType) {
+        let bit = SudokuBoard::value_mask(value);
+
+        self.values[index] = Some(value);
+        self.candidates[index] = bit;
+
+        for &peer in &self.layout.peers[index] {
+            self.candidates[peer] &= !bit;
+        }
+    }
+}
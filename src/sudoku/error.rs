@@ -0,0 +1,207 @@
+use std::{error::Error, fmt, io};
+
+use crate::sudoku::board::{CellType, SudokuBoard, Unit};
+
+/// Two givens holding the same value in one unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub value: CellType,
+    pub first: (usize, usize),
+    pub second: (usize, usize),
+    pub unit: Unit,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at row {}, column {} and row {}, column {} in {}",
+            SudokuBoard::value_to_char(self.value),
+            self.first.0 + 1,
+            self.first.1 + 1,
+            self.second.0 + 1,
+            self.second.1 + 1,
+            self.unit
+        )
+    }
+}
+
+/// Everything that can go wrong building, filling or loading a board.
+/// Coordinates are 0-based like the rest of the API, messages show them
+/// 1-based.
+#[derive(Debug)]
+pub enum SudokuError {
+    InvalidBoxSize {
+        box_width: usize,
+        box_height: usize,
+    },
+    /// `expected` is `None` when the box shape is inferred from the rows and
+    /// their number has none.
+    WrongRowCount {
+        expected: Option<usize>,
+        found: usize,
+    },
+    /// A one-line puzzle whose length isn't the square of a board size.
+    InvalidLineLength {
+        length: usize,
+    },
+    WrongRowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    ValueOutOfRange {
+        x: usize,
+        y: usize,
+        value: CellType,
+        max: usize,
+    },
+    InvalidCoordinates {
+        x: usize,
+        y: usize,
+    },
+    /// `conflict_with` is the cell already holding `value` in the same row,
+    /// column or box.
+    ConflictingValue {
+        x: usize,
+        y: usize,
+        value: CellType,
+        conflict_with: (usize, usize),
+    },
+    /// Every pair of givens that repeat a value, once per unit they share.
+    ConflictingGivens(Vec<Conflict>),
+    /// The cell at `x`, `y` is still empty.
+    Incomplete {
+        x: usize,
+        y: usize,
+    },
+    NoSolution,
+    Io {
+        path: String,
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SudokuError::InvalidBoxSize {
+                box_width,
+                box_height,
+            } => write!(f, "Invalid box size {}x{}", box_height, box_width),
+            SudokuError::WrongRowCount {
+                expected: Some(expected),
+                found,
+            } => write!(f, "The board must have {} rows, found {}", expected, found),
+            SudokuError::WrongRowCount {
+                expected: None,
+                found,
+            } => write!(
+                f,
+                "The board must have a number of rows that splits into boxes of at least 2x2, found {}",
+                found
+            ),
+            SudokuError::InvalidLineLength { length } => write!(
+                f,
+                "A one-line puzzle must have a square number of cells, found {}",
+                length
+            ),
+            SudokuError::WrongRowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} must have {} values, found {}",
+                row + 1,
+                expected,
+                found
+            ),
+            SudokuError::ValueOutOfRange { x, y, value, max } => write!(
+                f,
+                "Value {} at row {}, column {} must be between 1 and {}",
+                value,
+                x + 1,
+                y + 1,
+                max
+            ),
+            SudokuError::InvalidCoordinates { x, y } => {
+                write!(f, "Invalid coordinates ({}, {})", x, y)
+            }
+            SudokuError::ConflictingValue {
+                x,
+                y,
+                value,
+                conflict_with,
+            } => write!(
+                f,
+                "{} at row {}, column {} conflicts with row {}, column {}",
+                SudokuBoard::value_to_char(*value),
+                x + 1,
+                y + 1,
+                conflict_with.0 + 1,
+                conflict_with.1 + 1
+            ),
+            SudokuError::ConflictingGivens(conflicts) => {
+                write!(f, "The givens conflict:")?;
+                for conflict in conflicts {
+                    write!(f, "\n  {}", conflict)?;
+                }
+                Ok(())
+            }
+            SudokuError::Incomplete { x, y } => {
+                write!(f, "The cell at row {}, column {} is empty", x + 1, y + 1)
+            }
+            SudokuError::NoSolution => write!(f, "The puzzle has no solution"),
+            SudokuError::Io { path, source } => write!(f, "Couldn't read {}: {}", path, source),
+        }
+    }
+}
+
+impl Error for SudokuError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SudokuError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{board::Unit, parser::read_file};
+
+    #[test]
+    fn messages_count_rows_and_columns_from_one() {
+        let conflicts = SudokuError::ConflictingGivens(vec![Conflict {
+            value: 11,
+            first: (0, 0),
+            second: (0, 9),
+            unit: Unit::Row(0),
+        }]);
+        assert_eq!(
+            conflicts.to_string(),
+            "The givens conflict:\n  B at row 1, column 1 and row 1, column 10 in row 1"
+        );
+
+        let conflict = SudokuError::ConflictingValue {
+            x: 2,
+            y: 3,
+            value: 4,
+            conflict_with: (2, 8),
+        };
+        assert_eq!(
+            conflict.to_string(),
+            "4 at row 3, column 4 conflicts with row 3, column 9"
+        );
+    }
+
+    #[test]
+    fn io_errors_keep_their_source() {
+        let error = read_file("example/missing.txt").unwrap_err();
+
+        assert!(matches!(&error, SudokuError::Io { path, .. } if path == "example/missing.txt"));
+        assert!(error.source().is_some());
+    }
+}
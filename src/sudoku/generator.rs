@@ -0,0 +1,361 @@
+use crate::sudoku::{
+    algorithms::solutions::{SolutionCount, count_solutions},
+    board::{CellType, Grid, SudokuBoard},
+    error::SudokuError,
+    rating::{Difficulty, is_at_most, rate},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    None,
+    /// Removing a clue also removes the one rotated 180 degrees around the
+    /// centre of the board.
+    Rotational,
+    /// Removing a clue also removes its mirror across the vertical axis.
+    Mirror,
+}
+
+/// Full grids tried when aiming at a difficulty before settling for the
+/// hardest puzzle found. Each one is carved in full, with a uniqueness check
+/// and a logical solve after every removal, so a target that is rarely hit
+/// costs up to this many carves.
+const DIFFICULTY_ATTEMPTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClueTarget {
+    Clues(usize),
+    Difficulty(Difficulty),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorOptions {
+    pub seed: u64,
+    pub box_width: usize,
+    pub box_height: usize,
+    pub target: ClueTarget,
+    pub symmetry: Symmetry,
+}
+
+pub struct GeneratedPuzzle {
+    pub puzzle: Vec<Vec<Option<CellType>>>,
+    pub clues: usize,
+    pub difficulty: Difficulty,
+}
+
+impl GeneratedPuzzle {
+    /// Renders the puzzle in the one-line-per-row format `read_file` reads,
+    /// with `?` for empty cells.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for row in &self.puzzle {
+            for value in row {
+                text.push(value.map_or('?', SudokuBoard::value_to_char));
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+/// SplitMix64, small and good enough to shuffle cells and values while
+/// keeping every puzzle reproducible from its seed.
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+pub struct Generator {
+    options: GeneratorOptions,
+    random: Random,
+}
+
+impl Generator {
+    pub fn new(options: GeneratorOptions) -> Self {
+        Generator {
+            options,
+            random: Random::new(options.seed),
+        }
+    }
+
+    fn board_max_number(&self) -> usize {
+        self.options.box_width * self.options.box_height
+    }
+
+    fn board(&self, puzzle: &[Vec<Option<CellType>>]) -> Result<SudokuBoard, SudokuError> {
+        SudokuBoard::with_box_size(
+            puzzle.to_vec(),
+            self.options.box_width,
+            self.options.box_height,
+        )
+    }
+
+    /// Fills the boxes on the diagonal with shuffled values, which never
+    /// conflict with each other, and lets the solver complete the rest.
+    fn full_grid(&mut self) -> Result<Grid, SudokuError> {
+        let board_max_number = self.board_max_number();
+        let (box_width, box_height) = (self.options.box_width, self.options.box_height);
+        let mut puzzle = vec![vec![None; board_max_number]; board_max_number];
+
+        for diagonal in 0..box_width.min(box_height) {
+            let mut values: Vec<CellType> = (1..=board_max_number as CellType).collect();
+            self.random.shuffle(&mut values);
+
+            for (offset, value) in values.into_iter().enumerate() {
+                let x = diagonal * box_height + offset / box_width;
+                let y = diagonal * box_width + offset % box_width;
+                puzzle[x][y] = Some(value);
+            }
+        }
+
+        let board = self.board(&puzzle)?;
+        let solution = match count_solutions(&board, 2) {
+            SolutionCount::NoSolution => return Err(SudokuError::NoSolution),
+            SolutionCount::Unique(grid) => grid,
+            SolutionCount::Multiple { first_two, .. } => {
+                let [first, _] = first_two;
+                first
+            }
+        };
+
+        // The solver always tries values in order, relabel them so the rest
+        // of the grid is as random as the diagonal.
+        let mut relabel: Vec<CellType> = (1..=board_max_number as CellType).collect();
+        self.random.shuffle(&mut relabel);
+
+        Ok(solution
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| relabel[value as usize - 1])
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Groups every cell with the ones that must be removed together to keep
+    /// the requested symmetry.
+    fn removal_groups(&self) -> Vec<Vec<(usize, usize)>> {
+        let last = self.board_max_number() - 1;
+        let mut groups = Vec::new();
+
+        for x in 0..=last {
+            for y in 0..=last {
+                let partner = match self.options.symmetry {
+                    Symmetry::None => (x, y),
+                    Symmetry::Rotational => (last - x, last - y),
+                    Symmetry::Mirror => (x, last - y),
+                };
+
+                if partner == (x, y) {
+                    groups.push(vec![(x, y)]);
+                } else if (x, y) < partner {
+                    groups.push(vec![(x, y), partner]);
+                }
+            }
+        }
+
+        groups
+    }
+
+    /// Difficulty targets have no clue count, they remove as much as the
+    /// rating allows.
+    fn target_clues(&self) -> usize {
+        match self.options.target {
+            ClueTarget::Clues(clues) => clues.min(self.board_max_number().pow(2)),
+            ClueTarget::Difficulty(_) => 0,
+        }
+    }
+
+    /// A removal is kept only if the solution stays unique and, when aiming
+    /// at a difficulty, the puzzle doesn't get rated above it. The rating is
+    /// only looked at once the solution is known to be unique.
+    fn keeps_target(&self, puzzle: &[Vec<Option<CellType>>]) -> Result<bool, SudokuError> {
+        let board = self.board(puzzle)?;
+
+        if !matches!(count_solutions(&board, 2), SolutionCount::Unique(_)) {
+            return Ok(false);
+        }
+
+        match self.options.target {
+            ClueTarget::Clues(_) => Ok(true),
+            ClueTarget::Difficulty(difficulty) => Ok(is_at_most(&board, difficulty)),
+        }
+    }
+
+    /// Puzzles carved from a grid are often easier than asked for, so a
+    /// difficulty target tries several grids and keeps the first one rated
+    /// right, or the hardest one.
+    pub fn generate(&mut self) -> Result<GeneratedPuzzle, SudokuError> {
+        let ClueTarget::Difficulty(difficulty) = self.options.target else {
+            return self.carve();
+        };
+        let mut hardest = self.carve()?;
+
+        for _ in 1..DIFFICULTY_ATTEMPTS {
+            if hardest.difficulty == difficulty {
+                break;
+            }
+
+            let generated = self.carve()?;
+            if generated.difficulty > hardest.difficulty {
+                hardest = generated;
+            }
+        }
+
+        Ok(hardest)
+    }
+
+    /// Removes clues in random order, putting back any removal that breaks
+    /// the target, until the clue count is reached or no clue can go. The
+    /// result may keep more clues than asked for.
+    fn carve(&mut self) -> Result<GeneratedPuzzle, SudokuError> {
+        let solution = self.full_grid()?;
+        let target_clues = self.target_clues();
+        let mut puzzle: Vec<Vec<Option<CellType>>> = solution
+            .iter()
+            .map(|row| row.iter().map(|&value| Some(value)).collect())
+            .collect();
+        let mut clues = self.board_max_number().pow(2);
+        let mut groups = self.removal_groups();
+        self.random.shuffle(&mut groups);
+
+        for group in groups {
+            if clues <= target_clues {
+                break;
+            }
+
+            if clues < target_clues + group.len() {
+                continue;
+            }
+
+            for &(x, y) in &group {
+                puzzle[x][y] = None;
+            }
+
+            if self.keeps_target(&puzzle)? {
+                clues -= group.len();
+            } else {
+                for &(x, y) in &group {
+                    puzzle[x][y] = Some(solution[x][y]);
+                }
+            }
+        }
+
+        let difficulty = rate(&self.board(&puzzle)?)?.difficulty;
+
+        Ok(GeneratedPuzzle {
+            puzzle,
+            clues,
+            difficulty,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::parser::parse;
+
+    fn options(target: ClueTarget, symmetry: Symmetry) -> GeneratorOptions {
+        GeneratorOptions {
+            seed: 7,
+            box_width: 3,
+            box_height: 3,
+            target,
+            symmetry,
+        }
+    }
+
+    fn generate(options: GeneratorOptions) -> GeneratedPuzzle {
+        Generator::new(options).generate().unwrap()
+    }
+
+    #[test]
+    fn puzzles_have_a_unique_solution_and_the_clues_asked_for() {
+        let generated = generate(options(ClueTarget::Clues(30), Symmetry::None));
+        let board = SudokuBoard::new(generated.puzzle.clone()).unwrap();
+
+        assert!(matches!(
+            count_solutions(&board, 2),
+            SolutionCount::Unique(_)
+        ));
+        assert!(generated.clues >= 30);
+        assert_eq!(
+            generated.clues,
+            generated.puzzle.iter().flatten().flatten().count()
+        );
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_puzzle() {
+        let options = options(ClueTarget::Clues(30), Symmetry::Rotational);
+
+        assert_eq!(generate(options).puzzle, generate(options).puzzle);
+        assert_ne!(
+            generate(options).puzzle,
+            generate(GeneratorOptions { seed: 8, ..options }).puzzle
+        );
+    }
+
+    #[test]
+    fn removals_keep_the_symmetry() {
+        for symmetry in [Symmetry::Rotational, Symmetry::Mirror] {
+            let puzzle = generate(options(ClueTarget::Clues(30), symmetry)).puzzle;
+
+            for x in 0..9 {
+                for y in 0..9 {
+                    let partner = match symmetry {
+                        Symmetry::Rotational => puzzle[8 - x][8 - y],
+                        _ => puzzle[x][8 - y],
+                    };
+                    assert_eq!(puzzle[x][y].is_some(), partner.is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn difficulty_targets_are_not_exceeded() {
+        let generated = generate(GeneratorOptions {
+            box_width: 3,
+            box_height: 2,
+            ..options(ClueTarget::Difficulty(Difficulty::Easy), Symmetry::None)
+        });
+        let board = SudokuBoard::with_box_size(generated.puzzle.clone(), 3, 2).unwrap();
+
+        assert_eq!(generated.difficulty, Difficulty::Easy);
+        assert_eq!(rate(&board).unwrap().difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn text_is_read_back_as_the_same_puzzle() {
+        let generated = generate(options(ClueTarget::Clues(30), Symmetry::None));
+
+        assert_eq!(parse(&generated.to_text()), generated.puzzle);
+    }
+}
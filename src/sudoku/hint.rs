@@ -0,0 +1,97 @@
+use crate::sudoku::{
+    board::SudokuBoard,
+    techniques::{LogicalSolver, SolveStep},
+};
+
+/// The next value that can be placed by logic alone. The board keeps no
+/// pencil marks, so the eliminations that lead to it come along as
+/// `prerequisites`, easiest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub step: SolveStep,
+    pub prerequisites: Vec<SolveStep>,
+}
+
+/// Looks at the board as it is, filled values included, and never changes
+/// it. Returns `None` when the board is full or the known techniques can't
+/// place anything.
+pub fn hint(board: &SudokuBoard) -> Option<Hint> {
+    let mut solver = LogicalSolver::new(board);
+    let mut prerequisites = Vec::new();
+
+    while let Some(step) = solver.next_step() {
+        if !step.placements.is_empty() {
+            return Some(Hint {
+                step,
+                prerequisites,
+            });
+        }
+
+        solver.apply(&step);
+        prerequisites.push(step);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{
+        algorithms::{Algorithms, solve},
+        parser::parse,
+        techniques::Technique,
+    };
+
+    #[test]
+    fn hints_the_easiest_placement_without_changing_the_board() {
+        let board = SudokuBoard::new(parse(include_str!("../../example/easy.txt"))).unwrap();
+        let grid = board.grid();
+        let hint = hint(&board).unwrap();
+
+        assert_eq!(hint.step.technique, Technique::HiddenSingle);
+        assert_eq!(hint.step.to_string().split(" => ").nth(1), Some("r2c3=7"));
+        assert!(hint.prerequisites.is_empty());
+        assert_eq!(board.grid(), grid);
+    }
+
+    #[test]
+    fn eliminations_come_along_when_no_single_is_left() {
+        let board = SudokuBoard::new(parse(include_str!("../../example/expert.txt"))).unwrap();
+        let mut solver = LogicalSolver::new(&board);
+
+        // Play the placements until the next one needs eliminations first.
+        while let Some(step) = solver.next_step()
+            && !step.placements.is_empty()
+        {
+            solver.apply(&step);
+        }
+
+        let grid = solver.grid();
+        let partial = grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&value| (value != 0).then_some(value))
+                    .collect()
+            })
+            .collect();
+        let hint = hint(&SudokuBoard::new(partial).unwrap()).unwrap();
+
+        assert!(!hint.prerequisites.is_empty());
+        assert!(
+            hint.prerequisites
+                .iter()
+                .all(|step| step.placements.is_empty())
+        );
+        assert!(!hint.step.placements.is_empty());
+    }
+
+    #[test]
+    fn a_full_board_has_no_hint() {
+        let mut board = SudokuBoard::new(parse(include_str!("../../example/easy.txt"))).unwrap();
+        solve(&mut board, Algorithms::DancingLinks);
+
+        assert_eq!(hint(&board), None);
+    }
+}
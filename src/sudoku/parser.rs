@@ -0,0 +1,378 @@
+use std::{fmt, fs::read_to_string, path::Path};
+
+use crate::sudoku::{
+    board::{CandidateMask, CellType, SudokuBoard},
+    error::SudokuError,
+};
+
+/// The givens of a puzzle, row by row, `None` for an empty cell.
+pub type Puzzle = Vec<Vec<Option<CellType>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFormat {
+    /// One line per row, one character per cell.
+    Rows,
+    /// A whole puzzle on a single line.
+    Line,
+    /// Rows with box separators (`|`, `-`, `+`) and spaces between cells.
+    Grid,
+    /// SadMan Sudoku: `#` metadata lines, then the rows, optionally under a
+    /// `[Puzzle]` section.
+    Sdk,
+    /// Several puzzles, one per line.
+    Sdm,
+}
+
+impl InputFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            InputFormat::Rows => "rows",
+            InputFormat::Line => "line",
+            InputFormat::Grid => "grid",
+            InputFormat::Sdk => "sdk",
+            InputFormat::Sdm => "sdm",
+        }
+    }
+
+    pub fn from_extension(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+
+        match extension.as_str() {
+            "sdk" => Some(InputFormat::Sdk),
+            "sdm" => Some(InputFormat::Sdm),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the text alone. Lines that each hold a whole
+    /// puzzle are read as `.sdm`, unless they are as long as there are lines
+    /// and could be the rows of a single board: a 4x4 `.sdm` with exactly 16
+    /// puzzles needs its extension to be told apart, a 9x9 one with 81 is
+    /// found since no board has 81 rows. `#` comments are left out of the
+    /// guess and read as `.sdk` metadata when the rest is rows.
+    pub fn detect(text: &str) -> Self {
+        let mut comments = false;
+        let mut lines: Vec<&str> = Vec::new();
+
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            match line.trim_start().chars().next() {
+                Some('[') => return InputFormat::Sdk,
+                Some('#') => comments = true,
+                _ => lines.push(line),
+            }
+        }
+
+        if lines.iter().any(|line| line.contains(['|', '+'])) {
+            return InputFormat::Grid;
+        }
+
+        let Some(first) = lines.first() else {
+            return InputFormat::Rows;
+        };
+        let length = first.chars().count();
+        let same_length = lines.iter().all(|line| line.chars().count() == length);
+
+        let could_be_rows = length == lines.len() && length <= CandidateMask::BITS as usize;
+
+        if lines.len() == 1 && !comments {
+            InputFormat::Line
+        } else if same_length && is_puzzle_length(length) && !could_be_rows {
+            InputFormat::Sdm
+        } else if same_length && length == lines.len() {
+            if comments {
+                InputFormat::Sdk
+            } else {
+                InputFormat::Rows
+            }
+        } else if lines
+            .iter()
+            .all(|line| line.chars().filter(|c| !c.is_whitespace()).count() == lines.len())
+        {
+            InputFormat::Grid
+        } else if comments {
+            InputFormat::Sdk
+        } else {
+            InputFormat::Rows
+        }
+    }
+
+    /// The extension wins over the content when it names a format.
+    pub fn for_file(path: &str, text: &str) -> Self {
+        InputFormat::from_extension(path).unwrap_or_else(|| InputFormat::detect(text))
+    }
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Whether a line of `length` cells can hold a whole board.
+fn is_puzzle_length(length: usize) -> bool {
+    let side = length.isqrt();
+    side * side == length
+        && side <= CandidateMask::BITS as usize
+        && SudokuBoard::box_size_from_lines(side).is_some()
+}
+
+fn value_from_char(value: char) -> Option<CellType> {
+    SudokuBoard::value_from_char(value).filter(|&value| value != 0)
+}
+
+/// Letters only stand for values on boards bigger than 9x9, on the others
+/// they are empty cells like any character that isn't a value.
+fn blank_letters(mut puzzle: Puzzle) -> Puzzle {
+    if puzzle.len() <= 9 {
+        for cell in puzzle.iter_mut().flatten() {
+            if cell.is_some_and(|value| value > 9) {
+                *cell = None;
+            }
+        }
+    }
+
+    puzzle
+}
+
+/// One line per row and one character per cell, anything that isn't a value
+/// (`?`, `.`, letters up to 9x9) is an empty cell, and so is `0`. Blank lines
+/// are skipped.
+pub fn parse(text: &str) -> Puzzle {
+    blank_letters(
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().map(value_from_char).collect())
+            .collect(),
+    )
+}
+
+/// A whole puzzle on one line, rows one after the other, as most puzzle
+/// collections store them: 81 characters for a 9x9. `.` and `0` are empty
+/// cells.
+pub fn parse_line(line: &str) -> Result<Puzzle, SudokuError> {
+    let cells: Vec<Option<CellType>> = line.trim().chars().map(value_from_char).collect();
+    let side = cells.len().isqrt();
+
+    if side == 0 || side * side != cells.len() {
+        return Err(SudokuError::InvalidLineLength {
+            length: cells.len(),
+        });
+    }
+
+    Ok(blank_letters(
+        cells
+            .chunks(side)
+            .map(<[Option<CellType>]>::to_vec)
+            .collect(),
+    ))
+}
+
+/// Rows drawn with box separators, such as
+///
+/// ```text
+/// 5 3 . | . 7 . | . . .
+/// ------+-------+------
+/// ```
+///
+/// Lines with nothing but separators and `#` comments are skipped, and
+/// spaces between cells are ignored, so blanks must be written as `.`, `0` or
+/// similar.
+pub fn parse_grid(text: &str) -> Puzzle {
+    let puzzle = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .map(|line| {
+            line.chars()
+                .filter(|&c| c != '|' && !c.is_whitespace())
+                .collect::<String>()
+        })
+        .filter(|line| !line.is_empty() && !line.chars().all(|c| matches!(c, '-' | '+' | '=')))
+        .map(|line| line.chars().map(value_from_char).collect())
+        .collect();
+
+    blank_letters(puzzle)
+}
+
+/// SadMan Sudoku `.sdk`: the rows under `[Puzzle]`, or the rows after the
+/// `#` metadata lines when there are no sections.
+pub fn parse_sdk(text: &str) -> Puzzle {
+    let has_sections = text.lines().any(|line| line.trim() == "[Puzzle]");
+    let mut in_puzzle = !has_sections;
+    let mut rows = String::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_puzzle = line == "[Puzzle]";
+        } else if in_puzzle && !line.is_empty() && !line.starts_with('#') {
+            rows.push_str(line);
+            rows.push('\n');
+        }
+    }
+
+    parse(&rows)
+}
+
+/// SadMan Sudoku `.sdm`: one puzzle per line, in the `parse_line` format.
+/// Blank lines and `#` comments are skipped.
+pub fn parse_sdm(text: &str) -> Result<Vec<Puzzle>, SudokuError> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_line)
+        .collect()
+}
+
+/// Every puzzle in `text`, which is a single one for all formats but `.sdm`.
+pub fn parse_puzzles(text: &str, format: InputFormat) -> Result<Vec<Puzzle>, SudokuError> {
+    match format {
+        InputFormat::Rows => Ok(vec![parse(text)]),
+        InputFormat::Line => parse_line(text).map(|puzzle| vec![puzzle]),
+        InputFormat::Grid => Ok(vec![parse_grid(text)]),
+        InputFormat::Sdk => Ok(vec![parse_sdk(text)]),
+        InputFormat::Sdm => parse_sdm(text),
+    }
+}
+
+/// Reads every puzzle in a file, telling its format from the extension or
+/// else the content.
+pub fn read_puzzles(file_path: &str) -> Result<Vec<Puzzle>, SudokuError> {
+    match read_to_string(file_path) {
+        Ok(file) => parse_puzzles(&file, InputFormat::for_file(file_path, &file)),
+        Err(source) => Err(SudokuError::Io {
+            path: file_path.to_string(),
+            source,
+        }),
+    }
+}
+
+/// Reads the first puzzle in a file, an empty one if it has none.
+pub fn read_file(file_path: &str) -> Result<Puzzle, SudokuError> {
+    read_puzzles(file_path).map(|puzzles| puzzles.into_iter().next().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASY: &str = include_str!("../../example/easy.txt");
+
+    #[test]
+    fn rows_skip_blank_lines() {
+        let puzzle = parse(&format!("\n{}\n\n", EASY.replace('\n', "\n\n")));
+
+        assert_eq!(puzzle.len(), 9);
+        assert_eq!(puzzle, parse(EASY));
+        assert_eq!(puzzle[0][..3], [None, Some(9), None]);
+    }
+
+    #[test]
+    fn letters_are_values_only_above_9x9() {
+        assert_eq!(
+            parse("1x3a\n3a1x\n2B4.\n4.2b\n")[0],
+            [Some(1), None, Some(3), None]
+        );
+        assert_eq!(parse_line(&"x".repeat(81)).unwrap(), vec![vec![None; 9]; 9]);
+
+        let mut sixteen = "G".repeat(16);
+        sixteen.push('\n');
+        assert_eq!(parse(&sixteen.repeat(16))[0][0], Some(16));
+    }
+
+    #[test]
+    fn a_9x9_sdm_with_81_puzzles_is_not_read_as_rows() {
+        let line = EASY.lines().collect::<String>();
+        let text = format!("{line}\n").repeat(81);
+
+        assert_eq!(InputFormat::detect(&text), InputFormat::Sdm);
+
+        let puzzles = parse_puzzles(&text, InputFormat::detect(&text)).unwrap();
+        assert_eq!(puzzles.len(), 81);
+        assert_eq!(puzzles[80], parse(EASY));
+    }
+
+    #[test]
+    fn lines_as_long_as_the_file_are_rows() {
+        let rows = "1234\n3412\n2143\n4321\n";
+        assert_eq!(InputFormat::detect(rows), InputFormat::Rows);
+        assert_eq!(InputFormat::detect(EASY), InputFormat::Rows);
+    }
+
+    #[test]
+    fn detects_every_format() {
+        let line = EASY.lines().collect::<String>();
+        let grid = "\
+. 9 . | . 3 2
+. 2 . | . 7 6
+------+------
+7 5 . | . 1 .
+";
+        let sdk = format!("#A Someone\n#D An easy one\n{EASY}");
+        let sections = format!("[Properties]\nA=Someone\n[Puzzle]\n{EASY}");
+
+        assert_eq!(InputFormat::detect(EASY), InputFormat::Rows);
+        assert_eq!(InputFormat::detect(&line), InputFormat::Line);
+        assert_eq!(InputFormat::detect(grid), InputFormat::Grid);
+        assert_eq!(InputFormat::detect(&sdk), InputFormat::Sdk);
+        assert_eq!(InputFormat::detect(&sections), InputFormat::Sdk);
+        assert_eq!(
+            InputFormat::detect(&format!("{line}\n{line}\n")),
+            InputFormat::Sdm
+        );
+    }
+
+    #[test]
+    fn every_format_reads_the_same_puzzle() {
+        let puzzle = parse(EASY);
+        let line = EASY.lines().collect::<String>().replace('?', ".");
+        let grid = EASY
+            .lines()
+            .enumerate()
+            .map(|(index, row)| {
+                let cells = row.chars().map(String::from).collect::<Vec<_>>();
+                let separator = if index % 3 == 2 {
+                    "\n------+-------+------"
+                } else {
+                    ""
+                };
+                format!(
+                    "{} | {} | {}{separator}",
+                    cells[..3].join(" "),
+                    cells[3..6].join(" "),
+                    cells[6..].join(" ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(parse_line(&line).unwrap(), puzzle);
+        assert_eq!(parse_grid(&grid), puzzle);
+        assert_eq!(parse_sdk(&format!("#A Someone\n{EASY}")), puzzle);
+        assert_eq!(
+            parse_sdk(&format!("[Puzzle]\n{EASY}\n[Solution]\n{EASY}")),
+            puzzle
+        );
+        assert_eq!(
+            parse_sdm(&format!("# two\n{line}\n\n{line}\n")).unwrap(),
+            [puzzle.clone(), puzzle]
+        );
+    }
+
+    #[test]
+    fn lines_that_are_not_a_square_are_refused() {
+        assert!(matches!(
+            parse_line(&".".repeat(80)),
+            Err(SudokuError::InvalidLineLength { length: 80 })
+        ));
+        assert!(matches!(
+            parse_sdm(&format!("{}\n{}\n", ".".repeat(81), ".".repeat(80))),
+            Err(SudokuError::InvalidLineLength { length: 80 })
+        ));
+    }
+
+    #[test]
+    fn the_extension_wins_over_the_content() {
+        assert_eq!(InputFormat::for_file("a.SDM", EASY), InputFormat::Sdm);
+        assert_eq!(InputFormat::for_file("a.sdk", EASY), InputFormat::Sdk);
+        assert_eq!(InputFormat::for_file("a.txt", EASY), InputFormat::Rows);
+    }
+}
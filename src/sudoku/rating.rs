@@ -0,0 +1,186 @@
+use std::fmt;
+
+use crate::sudoku::{
+    algorithms::{constraint_propagation::ConstraintPropagation, perf::PerfTracker},
+    board::SudokuBoard,
+    candidate_grid::CandidateGrid,
+    error::SudokuError,
+    techniques::{LogicalSolver, Technique},
+};
+
+/// Score given to puzzles the known techniques can't finish, in the range
+/// Sudoku Explainer uses for forcing chains.
+const TRIAL_AND_ERROR_SCORE: f32 = 9.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    /// Easy puzzles need hidden singles only, medium ones naked singles and
+    /// intersections, hard ones subsets, fish and wings.
+    pub fn from_score(score: f32) -> Self {
+        if score < 2.0 {
+            Difficulty::Easy
+        } else if score < 3.0 {
+            Difficulty::Medium
+        } else if score < 4.5 {
+            Difficulty::Hard
+        } else {
+            Difficulty::Expert
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    /// Score of the hardest technique needed, or the trial and error score
+    /// when the techniques run out.
+    pub score: f32,
+    pub hardest: Option<Technique>,
+    pub difficulty: Difficulty,
+    pub solved_logically: bool,
+    pub steps: usize,
+    /// Guesses the constraint propagation solver makes before finding the
+    /// first solution, which only knows singles.
+    pub branches: u64,
+}
+
+pub fn rate(board: &SudokuBoard) -> Result<Rating, SudokuError> {
+    let mut perf = PerfTracker::new();
+    let solvable =
+        ConstraintPropagation::search(CandidateGrid::from_board(board), &mut perf, &mut |_| true);
+
+    if !solvable {
+        return Err(SudokuError::NoSolution);
+    }
+
+    let mut solver = LogicalSolver::new(board);
+    let steps = solver.solve();
+    let hardest = steps.iter().map(|step| step.technique).max();
+    let solved_logically = solver.is_solved();
+    let score = if solved_logically {
+        hardest.map_or(0.0, Technique::score)
+    } else {
+        TRIAL_AND_ERROR_SCORE
+    };
+
+    Ok(Rating {
+        score,
+        hardest,
+        difficulty: Difficulty::from_score(score),
+        solved_logically,
+        steps: steps.len(),
+        branches: perf.branches(),
+    })
+}
+
+/// Whether `rate` would put a puzzle known to be solvable at `difficulty`
+/// or below. Skips the solvability search and stops at the first technique
+/// that is too hard, as a generator checks this after every removal.
+pub fn is_at_most(board: &SudokuBoard, difficulty: Difficulty) -> bool {
+    let mut solver = LogicalSolver::new(board);
+
+    while !solver.is_solved() {
+        let Some(step) = solver.next_step() else {
+            return Difficulty::from_score(TRIAL_AND_ERROR_SCORE) <= difficulty;
+        };
+
+        if Difficulty::from_score(step.technique.score()) > difficulty {
+            return false;
+        }
+        solver.apply(&step);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::parser::parse;
+
+    fn rating(text: &str) -> Result<Rating, SudokuError> {
+        rate(&SudokuBoard::new(parse(text)).unwrap())
+    }
+
+    #[test]
+    fn rates_by_the_hardest_technique_needed() {
+        let easy = rating(include_str!("../../example/easy.txt")).unwrap();
+        assert_eq!(easy.difficulty, Difficulty::Easy);
+        assert_eq!(easy.hardest, Some(Technique::HiddenSingle));
+        assert!(easy.solved_logically);
+        assert_eq!(easy.branches, 0);
+
+        let expert = rating(include_str!("../../example/expert.txt")).unwrap();
+        assert_eq!(expert.difficulty, Difficulty::Hard);
+        assert_eq!(expert.hardest, Some(Technique::NakedTriple));
+        assert_eq!(expert.score, Technique::NakedTriple.score());
+        assert!(expert.steps > easy.steps);
+    }
+
+    #[test]
+    fn puzzles_the_techniques_cant_finish_are_trial_and_error() {
+        let empty = rating("????\n????\n????\n????\n").unwrap();
+
+        assert!(!empty.solved_logically);
+        assert_eq!(empty.score, TRIAL_AND_ERROR_SCORE);
+        assert_eq!(empty.difficulty, Difficulty::Expert);
+    }
+
+    #[test]
+    fn unsolvable_puzzles_have_no_rating() {
+        assert!(matches!(
+            rating("12??\n??3?\n???4\n????\n"),
+            Err(SudokuError::NoSolution)
+        ));
+    }
+
+    #[test]
+    fn the_early_check_agrees_with_the_rating() {
+        for text in [
+            include_str!("../../example/easy.txt"),
+            include_str!("../../example/expert.txt"),
+            "????\n????\n????\n????\n",
+        ] {
+            let board = SudokuBoard::new(parse(text)).unwrap();
+            let rated = rate(&board).unwrap().difficulty;
+
+            for difficulty in [
+                Difficulty::Easy,
+                Difficulty::Medium,
+                Difficulty::Hard,
+                Difficulty::Expert,
+            ] {
+                assert_eq!(is_at_most(&board, difficulty), rated <= difficulty);
+            }
+        }
+    }
+
+    #[test]
+    fn difficulty_bands_follow_the_score() {
+        assert_eq!(Difficulty::from_score(1.5), Difficulty::Easy);
+        assert_eq!(Difficulty::from_score(2.0), Difficulty::Medium);
+        assert_eq!(Difficulty::from_score(3.0), Difficulty::Hard);
+        assert_eq!(Difficulty::from_score(4.5), Difficulty::Expert);
+    }
+}
@@ -0,0 +1,399 @@
+use std::fmt;
+
+use crate::sudoku::{
+    board::{CellType, Grid, SudokuBoard},
+    candidate_grid::CandidateGrid,
+};
+
+mod coloring;
+mod fish;
+mod intersections;
+mod singles;
+mod subsets;
+mod wings;
+
+/// The named techniques, ordered from the easiest to the hardest. The
+/// logical solver always applies the first one that makes progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    PointingPair,
+    BoxLineReduction,
+    NakedPair,
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    XYWing,
+    SimpleColoring,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 12] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::Swordfish,
+        Technique::HiddenTriple,
+        Technique::XYWing,
+        Technique::SimpleColoring,
+    ];
+
+    /// Difficulty on the Sudoku Explainer scale. Simple coloring has no
+    /// direct counterpart there and is rated like the Turbot Fish, the
+    /// closest single-value chain it has.
+    pub fn score(self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::PointingPair => 2.6,
+            Technique::BoxLineReduction => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::XYWing => 4.2,
+            Technique::SimpleColoring => 6.6,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::PointingPair => "Pointing Pair",
+            Technique::BoxLineReduction => "Box/Line Reduction",
+            Technique::NakedPair => "Naked Pair",
+            Technique::XWing => "X-Wing",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::XYWing => "XY-Wing",
+            Technique::SimpleColoring => "Simple Coloring",
+        }
+    }
+
+    fn find(self, grid: &CandidateGrid) -> Option<SolveStep> {
+        match self {
+            Technique::HiddenSingle => singles::hidden_single(grid),
+            Technique::NakedSingle => singles::naked_single(grid),
+            Technique::PointingPair => intersections::pointing(grid),
+            Technique::BoxLineReduction => intersections::box_line_reduction(grid),
+            Technique::NakedPair => subsets::naked_subset(grid, 2, self),
+            Technique::XWing => fish::fish(grid, 2, self),
+            Technique::HiddenPair => subsets::hidden_subset(grid, 2, self),
+            Technique::NakedTriple => subsets::naked_subset(grid, 3, self),
+            Technique::Swordfish => fish::fish(grid, 3, self),
+            Technique::HiddenTriple => subsets::hidden_subset(grid, 3, self),
+            Technique::XYWing => wings::xy_wing(grid),
+            Technique::SimpleColoring => coloring::simple_coloring(grid),
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub x: usize,
+    pub y: usize,
+    pub value: CellType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elimination {
+    pub x: usize,
+    pub y: usize,
+    pub value: CellType,
+}
+
+/// One deduction: the values it places, the candidates it removes and the
+/// cells that form the pattern it is based on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveStep {
+    pub technique: Technique,
+    pub placements: Vec<Placement>,
+    pub eliminations: Vec<Elimination>,
+    pub reason_cells: Vec<(usize, usize)>,
+    pub explanation: String,
+}
+
+impl SolveStep {
+    fn new(technique: Technique, explanation: String) -> Self {
+        SolveStep {
+            technique,
+            placements: Vec::new(),
+            eliminations: Vec::new(),
+            reason_cells: Vec::new(),
+            explanation,
+        }
+    }
+
+    fn with_reason_cells(mut self, grid: &CandidateGrid, cells: &[usize]) -> Self {
+        self.reason_cells = cells.iter().map(|&index| grid.coordinates(index)).collect();
+        self
+    }
+
+    fn place(mut self, grid: &CandidateGrid, index: usize, value: CellType) -> Self {
+        let (x, y) = grid.coordinates(index);
+        self.placements.push(Placement { x, y, value });
+        self
+    }
+
+    /// Records the elimination only when the candidate is still there.
+    fn eliminate(&mut self, grid: &CandidateGrid, index: usize, value: CellType) {
+        let (x, y) = grid.coordinates(index);
+        let already_listed = self
+            .eliminations
+            .iter()
+            .any(|elimination| (elimination.x, elimination.y, elimination.value) == (x, y, value));
+
+        if grid.value(index).is_none()
+            && grid.candidates(index) & SudokuBoard::value_mask(value) != 0
+            && !already_listed
+        {
+            self.eliminations.push(Elimination { x, y, value });
+        }
+    }
+
+    fn made_progress(self) -> Option<Self> {
+        if self.placements.is_empty() && self.eliminations.is_empty() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+impl fmt::Display for SolveStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut outcome: Vec<String> = self
+            .placements
+            .iter()
+            .map(|placement| {
+                format!(
+                    "{}={}",
+                    cell_name(placement.x, placement.y),
+                    SudokuBoard::value_to_char(placement.value)
+                )
+            })
+            .collect();
+        outcome.extend(self.eliminations.iter().map(|elimination| {
+            format!(
+                "{}<>{}",
+                cell_name(elimination.x, elimination.y),
+                SudokuBoard::value_to_char(elimination.value)
+            )
+        }));
+
+        write!(
+            f,
+            "{}: {} => {}",
+            self.technique,
+            self.explanation,
+            outcome.join(", ")
+        )
+    }
+}
+
+pub fn cell_name(x: usize, y: usize) -> String {
+    format!("r{}c{}", x + 1, y + 1)
+}
+
+fn cell_names(grid: &CandidateGrid, cells: &[usize]) -> String {
+    cells
+        .iter()
+        .map(|&index| {
+            let (x, y) = grid.coordinates(index);
+            cell_name(x, y)
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn value_names(values: impl Iterator<Item = CellType>) -> String {
+    values
+        .map(|value| SudokuBoard::value_to_char(value).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Empty cells of `cells` that still have `value` as a candidate.
+fn value_positions(grid: &CandidateGrid, cells: &[usize], value: CellType) -> Vec<usize> {
+    let bit = SudokuBoard::value_mask(value);
+
+    cells
+        .iter()
+        .copied()
+        .filter(|&index| grid.value(index).is_none() && grid.candidates(index) & bit != 0)
+        .collect()
+}
+
+fn is_placed(grid: &CandidateGrid, cells: &[usize], value: CellType) -> bool {
+    cells.iter().any(|&index| grid.value(index) == Some(value))
+}
+
+fn combinations(items: &[usize], size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+
+    let mut result = Vec::new();
+    for (position, &item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[position + 1..], size - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+
+    result
+}
+
+/// Solves with pencil marks only, one named technique at a time, and never
+/// guesses. Puzzles that need more than the known techniques are left
+/// partially filled.
+pub struct LogicalSolver {
+    grid: CandidateGrid,
+}
+
+impl LogicalSolver {
+    pub fn new(board: &SudokuBoard) -> Self {
+        LogicalSolver {
+            grid: CandidateGrid::from_board(board),
+        }
+    }
+
+    /// The easiest deduction available on the current pencil marks, without
+    /// applying it.
+    pub fn next_step(&self) -> Option<SolveStep> {
+        Technique::ALL
+            .iter()
+            .find_map(|technique| technique.find(&self.grid))
+    }
+
+    pub fn apply(&mut self, step: &SolveStep) {
+        for placement in &step.placements {
+            let index = self.grid.index(placement.x, placement.y);
+            self.grid.place(index, placement.value);
+        }
+
+        for elimination in &step.eliminations {
+            let index = self.grid.index(elimination.x, elimination.y);
+            self.grid.eliminate(index, elimination.value);
+        }
+    }
+
+    /// Applies steps until the board is solved or no technique applies,
+    /// returning the trail.
+    pub fn solve(&mut self) -> Vec<SolveStep> {
+        let mut steps = Vec::new();
+
+        while !self.grid.is_solved() {
+            match self.next_step() {
+                Some(step) => {
+                    self.apply(&step);
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+
+        steps
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid.is_solved()
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.grid.grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{
+        algorithms::solutions::{SolutionCount, count_solutions},
+        parser::parse,
+    };
+
+    const EXAMPLES: [&str; 4] = [
+        include_str!("../../example/6x6.txt"),
+        include_str!("../../example/easy.txt"),
+        include_str!("../../example/expert.txt"),
+        include_str!("../../example/wikipedia.txt"),
+    ];
+
+    fn solution(board: &SudokuBoard) -> Grid {
+        match count_solutions(board, 2) {
+            SolutionCount::Unique(solution) => solution,
+            count => panic!("expected a unique solution, got {count:?}"),
+        }
+    }
+
+    #[test]
+    fn steps_never_contradict_the_solution() {
+        for text in EXAMPLES {
+            let board = SudokuBoard::new(parse(text)).unwrap();
+            let solution = solution(&board);
+            let mut solver = LogicalSolver::new(&board);
+
+            for step in solver.solve() {
+                for placement in &step.placements {
+                    assert_eq!(
+                        solution[placement.x][placement.y], placement.value,
+                        "{step}"
+                    );
+                }
+                for elimination in &step.eliminations {
+                    assert_ne!(
+                        solution[elimination.x][elimination.y], elimination.value,
+                        "{step}"
+                    );
+                }
+            }
+
+            assert!(solver.is_solved());
+            assert_eq!(solver.grid(), solution);
+        }
+    }
+
+    #[test]
+    fn easy_puzzles_need_singles_only() {
+        let board = SudokuBoard::new(parse(EXAMPLES[1])).unwrap();
+        let steps = LogicalSolver::new(&board).solve();
+
+        assert!(
+            steps
+                .iter()
+                .all(|step| step.technique <= Technique::NakedSingle)
+        );
+    }
+
+    #[test]
+    fn next_step_leaves_the_solver_untouched() {
+        let board = SudokuBoard::new(parse(EXAMPLES[2])).unwrap();
+        let mut solver = LogicalSolver::new(&board);
+        let step = solver.next_step().unwrap();
+
+        assert_eq!(solver.next_step(), Some(step.clone()));
+
+        solver.apply(&step);
+        assert_ne!(solver.next_step(), Some(step));
+    }
+}
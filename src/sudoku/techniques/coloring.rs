@@ -0,0 +1,148 @@
+use crate::sudoku::{
+    board::{CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{SolveStep, Technique, cell_names, is_placed, value_positions},
+};
+
+/// Chains of conjugate pairs (units where a value has exactly two places)
+/// colored alternately: exactly one of the two colors holds the value. A
+/// color appearing twice in a unit is false, and any cell seeing both colors
+/// can't hold the value.
+pub fn simple_coloring(grid: &CandidateGrid) -> Option<SolveStep> {
+    let board_max_number = grid.board_max_number();
+
+    for value in 1..=board_max_number as CellType {
+        let mut links: Vec<(usize, usize)> = Vec::new();
+        for cells in grid.units() {
+            if is_placed(grid, cells, value) {
+                continue;
+            }
+
+            if let [first, second] = value_positions(grid, cells, value)[..] {
+                links.push((first, second));
+            }
+        }
+
+        let mut colors: Vec<Option<bool>> = vec![None; grid.cell_count()];
+        for &(start, _) in &links {
+            if colors[start].is_some() {
+                continue;
+            }
+
+            let chain = color_chain(&links, &mut colors, start);
+            if chain.len() < 3 {
+                continue;
+            }
+
+            if let Some(step) = color_wrap(grid, &colors, &chain, value)
+                .or_else(|| color_trap(grid, &colors, &chain, value))
+            {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+fn color_chain(links: &[(usize, usize)], colors: &mut [Option<bool>], start: usize) -> Vec<usize> {
+    let mut chain = vec![start];
+    let mut pending = vec![start];
+    colors[start] = Some(true);
+
+    while let Some(index) = pending.pop() {
+        let color = colors[index].unwrap();
+
+        for &(first, second) in links {
+            let other = if first == index {
+                second
+            } else if second == index {
+                first
+            } else {
+                continue;
+            };
+
+            if colors[other].is_none() {
+                colors[other] = Some(!color);
+                chain.push(other);
+                pending.push(other);
+            }
+        }
+    }
+
+    chain
+}
+
+fn chain_cells(colors: &[Option<bool>], chain: &[usize], color: bool) -> Vec<usize> {
+    chain
+        .iter()
+        .copied()
+        .filter(|&index| colors[index] == Some(color))
+        .collect()
+}
+
+fn color_wrap(
+    grid: &CandidateGrid,
+    colors: &[Option<bool>],
+    chain: &[usize],
+    value: CellType,
+) -> Option<SolveStep> {
+    for color in [true, false] {
+        let cells = chain_cells(colors, chain, color);
+        let clash = cells.iter().enumerate().find_map(|(position, &first)| {
+            cells[position + 1..]
+                .iter()
+                .find(|&&second| grid.sees(first, second))
+                .map(|&second| (first, second))
+        });
+
+        if let Some((first, second)) = clash {
+            let explanation = format!(
+                "the chain on {} gives {} and {} the same color, so that color can't be {}",
+                SudokuBoard::value_to_char(value),
+                cell_names(grid, &[first]),
+                cell_names(grid, &[second]),
+                SudokuBoard::value_to_char(value)
+            );
+            let mut step = SolveStep::new(Technique::SimpleColoring, explanation)
+                .with_reason_cells(grid, chain);
+
+            for &index in &cells {
+                step.eliminate(grid, index, value);
+            }
+
+            return step.made_progress();
+        }
+    }
+
+    None
+}
+
+fn color_trap(
+    grid: &CandidateGrid,
+    colors: &[Option<bool>],
+    chain: &[usize],
+    value: CellType,
+) -> Option<SolveStep> {
+    let on = chain_cells(colors, chain, true);
+    let off = chain_cells(colors, chain, false);
+    let explanation = format!(
+        "either all of {} or all of {} are {}",
+        cell_names(grid, &on),
+        cell_names(grid, &off),
+        SudokuBoard::value_to_char(value)
+    );
+    let mut step =
+        SolveStep::new(Technique::SimpleColoring, explanation).with_reason_cells(grid, chain);
+
+    for index in (0..grid.cell_count()).filter(|index| !chain.contains(index)) {
+        let sees_on = on.iter().any(|&cell| grid.sees(index, cell));
+        let sees_off = off.iter().any(|&cell| grid.sees(index, cell));
+
+        if sees_on && sees_off {
+            step.eliminate(grid, index, value);
+        }
+    }
+
+    step.made_progress()
+}
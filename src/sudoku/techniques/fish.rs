@@ -0,0 +1,79 @@
+use crate::sudoku::{
+    board::{CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{SolveStep, Technique, combinations, is_placed, value_positions},
+};
+
+/// X-Wing for `size` 2 and Swordfish for 3: a value confined to the same
+/// `size` columns across `size` rows can't appear anywhere else in those
+/// columns, and the same with rows and columns swapped.
+pub fn fish(grid: &CandidateGrid, size: usize, technique: Technique) -> Option<SolveStep> {
+    let board_max_number = grid.board_max_number();
+    let units = grid.units();
+
+    for value in 1..=board_max_number as CellType {
+        // Rows as base lines covered by columns, then the other way around.
+        for (base_offset, cover_offset) in [(0, board_max_number), (board_max_number, 0)] {
+            let line_position = |index: usize| {
+                let (x, y) = grid.coordinates(index);
+                if base_offset == 0 { y } else { x }
+            };
+            let base_lines: Vec<usize> = (base_offset..base_offset + board_max_number)
+                .filter(|&line| {
+                    let positions = value_positions(grid, &units[line], value).len();
+                    !is_placed(grid, &units[line], value) && (2..=size).contains(&positions)
+                })
+                .collect();
+
+            for lines in combinations(&base_lines, size) {
+                let mut positions: Vec<usize> = Vec::new();
+                let mut cover: Vec<usize> = Vec::new();
+
+                for &line in &lines {
+                    for index in value_positions(grid, &units[line], value) {
+                        positions.push(index);
+                        if !cover.contains(&line_position(index)) {
+                            cover.push(line_position(index));
+                        }
+                    }
+                }
+
+                if cover.len() != size {
+                    continue;
+                }
+
+                cover.sort_unstable();
+                let explanation = format!(
+                    "{} in {} is confined to {}",
+                    SudokuBoard::value_to_char(value),
+                    lines
+                        .iter()
+                        .map(|&line| grid.unit(line).to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    cover
+                        .iter()
+                        .map(|&line| grid.unit(cover_offset + line).to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+                let mut step =
+                    SolveStep::new(technique, explanation).with_reason_cells(grid, &positions);
+
+                for &line in &cover {
+                    for &index in &units[cover_offset + line] {
+                        if !positions.contains(&index) {
+                            step.eliminate(grid, index, value);
+                        }
+                    }
+                }
+
+                if let Some(step) = step.made_progress() {
+                    return Some(step);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,106 @@
+use crate::sudoku::{
+    board::{CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{SolveStep, Technique, cell_names, is_placed, value_positions},
+};
+
+/// The units containing every cell of `positions`, leaving boxes out when
+/// `lines_only` is set.
+fn shared_unit(grid: &CandidateGrid, positions: &[usize], lines_only: bool) -> Vec<usize> {
+    let board_max_number = grid.board_max_number();
+    let (first_x, first_y) = grid.coordinates(positions[0]);
+    let mut shared = Vec::new();
+
+    if positions
+        .iter()
+        .all(|&index| grid.coordinates(index).0 == first_x)
+    {
+        shared.push(first_x);
+    }
+
+    if positions
+        .iter()
+        .all(|&index| grid.coordinates(index).1 == first_y)
+    {
+        shared.push(board_max_number + first_y);
+    }
+
+    if !lines_only {
+        let first_box = grid.box_of(positions[0]);
+
+        if positions
+            .iter()
+            .all(|&index| grid.box_of(index) == first_box)
+        {
+            shared.push(board_max_number * 2 + first_box);
+        }
+    }
+
+    shared
+}
+
+/// A value confined to one row or column inside a box can't appear in the
+/// rest of that row or column.
+pub fn pointing(grid: &CandidateGrid) -> Option<SolveStep> {
+    intersection(grid, Technique::PointingPair)
+}
+
+/// A value confined to one box inside a row or column can't appear in the
+/// rest of that box.
+pub fn box_line_reduction(grid: &CandidateGrid) -> Option<SolveStep> {
+    intersection(grid, Technique::BoxLineReduction)
+}
+
+fn intersection(grid: &CandidateGrid, technique: Technique) -> Option<SolveStep> {
+    let board_max_number = grid.board_max_number();
+    let units = grid.units();
+    let source_units = match technique {
+        Technique::PointingPair => board_max_number * 2..board_max_number * 3,
+        _ => 0..board_max_number * 2,
+    };
+
+    for unit_index in source_units {
+        let cells = &units[unit_index];
+
+        for value in 1..=board_max_number as CellType {
+            if is_placed(grid, cells, value) {
+                continue;
+            }
+
+            let positions = value_positions(grid, cells, value);
+            if positions.len() < 2 {
+                continue;
+            }
+
+            let is_box = technique == Technique::PointingPair;
+            for target_unit in shared_unit(grid, &positions, is_box) {
+                if target_unit == unit_index {
+                    continue;
+                }
+
+                let explanation = format!(
+                    "{} in {} can only go in {}, which are all in {}",
+                    SudokuBoard::value_to_char(value),
+                    grid.unit(unit_index),
+                    cell_names(grid, &positions),
+                    grid.unit(target_unit)
+                );
+                let mut step =
+                    SolveStep::new(technique, explanation).with_reason_cells(grid, &positions);
+
+                for &index in units[target_unit]
+                    .iter()
+                    .filter(|index| !positions.contains(index))
+                {
+                    step.eliminate(grid, index, value);
+                }
+
+                if let Some(step) = step.made_progress() {
+                    return Some(step);
+                }
+            }
+        }
+    }
+
+    None
+}
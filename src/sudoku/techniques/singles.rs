@@ -0,0 +1,55 @@
+use crate::sudoku::{
+    board::{CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{SolveStep, Technique, cell_names, is_placed, value_positions},
+};
+
+pub fn naked_single(grid: &CandidateGrid) -> Option<SolveStep> {
+    (0..grid.cell_count())
+        .find(|&index| grid.value(index).is_none() && grid.candidates(index).count_ones() == 1)
+        .map(|index| {
+            let value = grid.candidates(index).trailing_zeros() as CellType + 1;
+            let explanation = format!(
+                "{} has no candidate left but {}",
+                cell_names(grid, &[index]),
+                SudokuBoard::value_to_char(value)
+            );
+
+            SolveStep::new(Technique::NakedSingle, explanation)
+                .place(grid, index, value)
+                .with_reason_cells(grid, &[index])
+        })
+}
+
+pub fn hidden_single(grid: &CandidateGrid) -> Option<SolveStep> {
+    let units = grid.units();
+    let board_max_number = grid.board_max_number();
+
+    // Boxes first, singles there are the easiest to spot.
+    for unit_index in (0..units.len()).map(|index| (index + board_max_number * 2) % units.len()) {
+        let cells = &units[unit_index];
+
+        for value in 1..=board_max_number as CellType {
+            if is_placed(grid, cells, value) {
+                continue;
+            }
+
+            if let [index] = value_positions(grid, cells, value)[..] {
+                let explanation = format!(
+                    "{} is the only place left for {} in {}",
+                    cell_names(grid, &[index]),
+                    SudokuBoard::value_to_char(value),
+                    grid.unit(unit_index)
+                );
+
+                return Some(
+                    SolveStep::new(Technique::HiddenSingle, explanation)
+                        .place(grid, index, value)
+                        .with_reason_cells(grid, cells),
+                );
+            }
+        }
+    }
+
+    None
+}
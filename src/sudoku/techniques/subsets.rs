@@ -0,0 +1,108 @@
+use crate::sudoku::{
+    board::{CandidateMask, CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{
+        SolveStep, Technique, cell_names, combinations, is_placed, value_names, value_positions,
+    },
+};
+
+/// `size` cells of a unit that share exactly `size` candidates between
+/// them: those values can go nowhere else in the unit.
+pub fn naked_subset(grid: &CandidateGrid, size: usize, technique: Technique) -> Option<SolveStep> {
+    for (unit_index, cells) in grid.units().iter().enumerate() {
+        let subset_cells: Vec<usize> = cells
+            .iter()
+            .copied()
+            .filter(|&index| {
+                grid.value(index).is_none()
+                    && (2..=size as u32).contains(&grid.candidates(index).count_ones())
+            })
+            .collect();
+
+        for subset in combinations(&subset_cells, size) {
+            let values: CandidateMask = subset
+                .iter()
+                .fold(0, |mask, &index| mask | grid.candidates(index));
+
+            if values.count_ones() as usize != size {
+                continue;
+            }
+
+            let explanation = format!(
+                "{} can only hold {} in {}",
+                cell_names(grid, &subset),
+                value_names(SudokuBoard::mask_values(values)),
+                grid.unit(unit_index)
+            );
+            let mut step = SolveStep::new(technique, explanation).with_reason_cells(grid, &subset);
+
+            for &index in cells.iter().filter(|index| !subset.contains(index)) {
+                for value in SudokuBoard::mask_values(grid.candidates(index) & values) {
+                    step.eliminate(grid, index, value);
+                }
+            }
+
+            if let Some(step) = step.made_progress() {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+/// `size` values of a unit confined to the same `size` cells: those cells
+/// can hold nothing else.
+pub fn hidden_subset(grid: &CandidateGrid, size: usize, technique: Technique) -> Option<SolveStep> {
+    let board_max_number = grid.board_max_number();
+
+    for (unit_index, cells) in grid.units().iter().enumerate() {
+        let subset_values: Vec<usize> = (1..=board_max_number)
+            .filter(|&value| {
+                let positions = value_positions(grid, cells, value as CellType).len();
+                !is_placed(grid, cells, value as CellType) && (2..=size).contains(&positions)
+            })
+            .collect();
+
+        for subset in combinations(&subset_values, size) {
+            let mut subset_cells: Vec<usize> = Vec::new();
+            for &value in &subset {
+                for index in value_positions(grid, cells, value as CellType) {
+                    if !subset_cells.contains(&index) {
+                        subset_cells.push(index);
+                    }
+                }
+            }
+
+            if subset_cells.len() != size {
+                continue;
+            }
+
+            let values: CandidateMask = subset.iter().fold(0, |mask, &value| {
+                mask | SudokuBoard::value_mask(value as CellType)
+            });
+            subset_cells.sort_unstable();
+
+            let explanation = format!(
+                "{} can only go in {} within {}",
+                value_names(SudokuBoard::mask_values(values)),
+                cell_names(grid, &subset_cells),
+                grid.unit(unit_index)
+            );
+            let mut step =
+                SolveStep::new(technique, explanation).with_reason_cells(grid, &subset_cells);
+
+            for &index in &subset_cells {
+                for value in SudokuBoard::mask_values(grid.candidates(index) & !values) {
+                    step.eliminate(grid, index, value);
+                }
+            }
+
+            if let Some(step) = step.made_progress() {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
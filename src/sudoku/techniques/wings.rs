@@ -0,0 +1,65 @@
+use crate::sudoku::{
+    board::{CandidateMask, CellType, SudokuBoard},
+    candidate_grid::CandidateGrid,
+    techniques::{SolveStep, Technique, cell_names},
+};
+
+fn is_bivalue(grid: &CandidateGrid, index: usize) -> bool {
+    grid.value(index).is_none() && grid.candidates(index).count_ones() == 2
+}
+
+/// A pivot with candidates `ab` that sees one pincer with `ac` and another
+/// with `bc`: whichever value the pivot takes, one pincer is `c`, so cells
+/// seeing both pincers can't be `c`.
+pub fn xy_wing(grid: &CandidateGrid) -> Option<SolveStep> {
+    for pivot in (0..grid.cell_count()).filter(|&index| is_bivalue(grid, index)) {
+        let pivot_candidates = grid.candidates(pivot);
+        let pincers: Vec<usize> = grid
+            .peers(pivot)
+            .iter()
+            .copied()
+            .filter(|&index| {
+                is_bivalue(grid, index)
+                    && (grid.candidates(index) & pivot_candidates).count_ones() == 1
+            })
+            .collect();
+
+        for (position, &first) in pincers.iter().enumerate() {
+            for &second in &pincers[position + 1..] {
+                let first_candidates = grid.candidates(first);
+                let second_candidates = grid.candidates(second);
+                let shared: CandidateMask = first_candidates & second_candidates;
+
+                if first_candidates & pivot_candidates == second_candidates & pivot_candidates
+                    || shared.count_ones() != 1
+                    || shared & pivot_candidates != 0
+                {
+                    continue;
+                }
+
+                let value = shared.trailing_zeros() as CellType + 1;
+                let explanation = format!(
+                    "pivot {} with pincers {} and {}: one of the pincers is {}",
+                    cell_names(grid, &[pivot]),
+                    cell_names(grid, &[first]),
+                    cell_names(grid, &[second]),
+                    SudokuBoard::value_to_char(value)
+                );
+                let mut step = SolveStep::new(Technique::XYWing, explanation)
+                    .with_reason_cells(grid, &[pivot, first, second]);
+
+                for &index in grid.peers(first) {
+                    if index != pivot && index != second && grid.sees(index, second) {
+                        step.eliminate(grid, index, value);
+                    }
+                }
+
+                if let Some(step) = step.made_progress() {
+                    return Some(step);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,141 @@
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sudoku"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn solve_json_output_is_a_single_document() {
+    let output = run(&["example/easy.txt", "--output", "json"]);
+    let document: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(document["status"], "solved");
+    assert_eq!(document["algorithm"], "candidate election");
+    assert_eq!(document["input"][0][0], Value::Null);
+    assert_eq!(document["input"][0][1], 9);
+
+    let solution = document["solution"].as_array().unwrap();
+    assert_eq!(solution.len(), 9);
+    assert!(
+        solution
+            .iter()
+            .flat_map(|row| row.as_array().unwrap())
+            .all(|value| value.as_u64().is_some_and(|value| (1..=9).contains(&value)))
+    );
+}
+
+#[test]
+fn unsolved_json_output_has_no_solution() {
+    let output = run(&[
+        "example/expert.txt",
+        "--output",
+        "json",
+        "--max-actions",
+        "5",
+    ]);
+    let document: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(document["status"], "aborted");
+    assert_eq!(document["solution"], Value::Null);
+}
+
+#[test]
+fn batch_writes_one_json_document_per_line_to_any_file_name() {
+    let dir = std::env::temp_dir().join(format!("sudoku-batch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("puzzles.txt");
+    // A file called `json` is a path, the format comes from `--output`.
+    let out_file = dir.join("json");
+    std::fs::write(
+        &input,
+        "\
+..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..
+not a puzzle
+",
+    )
+    .unwrap();
+
+    let output = run(&[
+        "batch",
+        input.to_str().unwrap(),
+        "--output",
+        "json",
+        "--out-file",
+        out_file.to_str().unwrap(),
+    ]);
+    let written = std::fs::read_to_string(&out_file).unwrap();
+    let documents: Vec<Value> = written
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0]["status"], "solved");
+    assert_eq!(documents[0]["solution"][0][0], 4);
+    assert_eq!(documents[1]["status"], "invalid");
+}
+
+#[test]
+fn help_is_accepted_after_a_subcommand() {
+    for args in [
+        &["--help"][..],
+        &["example/easy.txt", "-h"],
+        &["generate", "--seed", "1", "--help"],
+        &["rate", "-h"],
+        &["batch", "--help"],
+    ] {
+        let output = run(args);
+
+        assert!(output.status.success(), "{args:?}");
+        assert!(output.stdout.starts_with(b"Usage:"), "{args:?}");
+    }
+}
+
+#[test]
+fn plain_output_can_be_read_back() {
+    let path = std::env::temp_dir().join(format!("sudoku-plain-{}.txt", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    let solved = run(&["example/easy.txt", "--output", "plain", "--color", "always"]);
+    std::fs::write(path, &solved.stdout).unwrap();
+    let again = run(&[path, "--output", "plain", "-a", "dlx"]);
+
+    assert!(solved.status.success());
+    assert_eq!(solved.stdout, again.stdout);
+    assert!(
+        String::from_utf8(solved.stdout)
+            .unwrap()
+            .lines()
+            .all(|line| line.len() == 9 && line.chars().all(|c| c.is_ascii_digit()))
+    );
+
+    let aborted = run(&[
+        "example/expert.txt",
+        "--output",
+        "plain",
+        "--max-actions",
+        "5",
+    ]);
+    std::fs::write(path, &aborted.stdout).unwrap();
+    let puzzle = sudoku::read_file(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert!(!aborted.status.success());
+    assert!(
+        String::from_utf8(aborted.stderr)
+            .unwrap()
+            .contains("Stopped")
+    );
+    assert!(sudoku::SudokuBoard::new(puzzle).is_ok());
+}